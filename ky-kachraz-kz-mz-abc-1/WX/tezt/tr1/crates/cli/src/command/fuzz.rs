@@ -1,11 +1,11 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Error};
 
 use clap::Subcommand;
 use fehler::throws;
 use heck::ToSnakeCase;
-use trident_client::___private::{Commander, TestGenerator};
+use trident_client::___private::{Commander, StatsExport, StatsFormat, TestGenerator};
 
 use crate::_discover;
 
@@ -40,7 +40,8 @@ pub enum FuzzCommand {
         override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
             \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to execute (for example fuzz_0).\
             \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
-            \n      trident fuzz run-afl fuzz_0"
+            \n      trident fuzz run-afl fuzz_0\
+            \n      trident fuzz run-afl fuzz_0 --stats-format junit --stats-output stats.xml"
     )]
     Run_Afl {
         #[arg(
@@ -48,13 +49,36 @@ pub enum FuzzCommand {
             help = "Name of the desired fuzz template to execute (for example fuzz_0)."
         )]
         target: String,
+        #[arg(
+            long,
+            required = false,
+            value_name = "FORMAT",
+            help = "Export the fuzzing statistics in the given format: json, csv or junit. Implies collecting the statistics."
+        )]
+        stats_format: Option<StatsFormat>,
+        #[arg(
+            long,
+            required = false,
+            value_name = "PATH",
+            requires = "stats_format",
+            help = "Write the exported statistics to the given file instead of the standard output."
+        )]
+        stats_output: Option<PathBuf>,
+        #[arg(
+            long,
+            required = false,
+            requires = "stats_format",
+            help = "Export the statistics accumulated across all sessions instead of the statistics of this session."
+        )]
+        stats_cumulative: bool,
     },
     #[command(
         about = "Run the Honggfuzz on desired fuzz test.",
         override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
             \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to execute (for example fuzz_0).\
             \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
-            \n      trident fuzz run-hfuzz fuzz_0"
+            \n      trident fuzz run-hfuzz fuzz_0\
+            \n      trident fuzz run-hfuzz fuzz_0 --stats-format junit --stats-output stats.xml"
     )]
     Run_Hfuzz {
         #[arg(
@@ -69,8 +93,199 @@ pub enum FuzzCommand {
             help = "Run the Honggfuzz with exit code, i.e. if it discovers crash the Trident will exit with exit code 1."
         )]
         with_exit_code: bool,
+        #[arg(
+            long,
+            required = false,
+            value_name = "FORMAT",
+            help = "Export the fuzzing statistics in the given format: json, csv or junit. Implies collecting the statistics."
+        )]
+        stats_format: Option<StatsFormat>,
+        #[arg(
+            long,
+            required = false,
+            value_name = "PATH",
+            requires = "stats_format",
+            help = "Write the exported statistics to the given file instead of the standard output."
+        )]
+        stats_output: Option<PathBuf>,
+        #[arg(
+            long,
+            required = false,
+            requires = "stats_format",
+            help = "Export the statistics accumulated across all sessions instead of the statistics of this session."
+        )]
+        stats_cumulative: bool,
+    },
+    #[command(
+        about = "Run the libFuzzer on desired fuzz test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to execute (for example fuzz_0).\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz run-libfuzzer fuzz_0\
+            \n      trident fuzz run-libfuzzer fuzz_0 --stats-format junit --stats-output stats.xml"
+    )]
+    Run_Libfuzzer {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to execute (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            long,
+            required = false,
+            value_name = "FORMAT",
+            help = "Export the fuzzing statistics in the given format: json, csv or junit. Implies collecting the statistics."
+        )]
+        stats_format: Option<StatsFormat>,
+        #[arg(
+            long,
+            required = false,
+            value_name = "PATH",
+            requires = "stats_format",
+            help = "Write the exported statistics to the given file instead of the standard output."
+        )]
+        stats_output: Option<PathBuf>,
+        #[arg(
+            long,
+            required = false,
+            requires = "stats_format",
+            help = "Export the statistics accumulated across all sessions instead of the statistics of this session."
+        )]
+        stats_cumulative: bool,
+    },
+    #[command(
+        about = "Minimize the corpus of the desired fuzz test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to minimize the corpus for (for example fuzz_0).\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m The following corpora are minimized:\
+            \n      \x1b[1m\x1b[4mHonggfuzz:\x1b[0m trident-tests/fuzzing/honggfuzz/hfuzz_workspace/<TARGET>/input (in place)\
+            \n      \x1b[1m\x1b[4mAFL:\x1b[0m trident-tests/fuzzing/afl/afl_workspace/out/<INSTANCE>/queue (into afl_workspace/minimized)\
+            \n      \x1b[1m\x1b[4mLibFuzzer:\x1b[0m trident-tests/fuzzing/libfuzzer/libfuzzer_workspace/corpus (in place)\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz minimize fuzz_0\
+            \n      trident fuzz minimize fuzz_0 --afl\
+            \n      trident fuzz minimize fuzz_0 --libfuzzer"
+    )]
+    Minimize {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to execute (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            short,
+            long,
+            required = false,
+            help = "Minimize the AFL queue instead of the Honggfuzz corpus."
+        )]
+        afl: bool,
+        #[arg(
+            short,
+            long,
+            required = false,
+            conflicts_with = "afl",
+            help = "Merge the libFuzzer corpus instead of the Honggfuzz corpus."
+        )]
+        libfuzzer: bool,
+    },
+    #[command(
+        about = "Replay all crashes of the desired fuzz test and group them by root cause.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to triage (for example fuzz_0).\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m The triage report is stored as triage.json in the crash directory (AFL output directory for AFL).\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz triage fuzz_0\
+            \n      trident fuzz triage fuzz_0 --afl"
+    )]
+    Triage {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to execute (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            short,
+            long,
+            required = false,
+            help = "Triage the crashes found by AFL instead of Honggfuzz."
+        )]
+        afl: bool,
     },
 
+    #[command(
+        about = "Show the statistics of the desired fuzz test collected across all fuzzing sessions.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to show the statistics for (for example fuzz_0).\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m The statistics are collected only with fuzzing_with_stats = true and stored in trident-tests/fuzzing/stats/<TARGET>.json\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz stats fuzz_0"
+    )]
+    Stats {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to execute (for example fuzz_0)."
+        )]
+        target: String,
+    },
+    #[command(
+        about = "Generate the coverage report of the programs from the saved corpus of the desired fuzz test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to generate the coverage report for (for example fuzz_0).\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m The corpora of Honggfuzz, AFL and libFuzzer are replayed unless --corpus is provided.\
+            \n      The HTML and lcov reports are stored in trident-tests/fuzzing/coverage/<TARGET>\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz coverage fuzz_0\
+            \n      trident fuzz coverage fuzz_0 --corpus trident-tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0/input"
+    )]
+    Coverage {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to execute (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            short,
+            long,
+            required = false,
+            value_name = "DIR",
+            help = "Replay the inputs of the given corpus directory instead of the saved corpora."
+        )]
+        corpus: Option<PathBuf>,
+        #[arg(
+            short,
+            long,
+            required = false,
+            default_value_t = 10,
+            value_name = "SECONDS",
+            help = "Skip the inputs which do not finish replaying within the given number of seconds."
+        )]
+        timeout: u64,
+    },
+    #[command(
+        about = "Reproduce found crash and optionally generate a regression test from it.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to reproduce the crash with (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m With --emit-test the regression test is stored in trident-tests/<TARGET>/regressions\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz reproduce fuzz_0 trident-tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0/SIGAR... --emit-test"
+    )]
+    Reproduce {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to execute (for example fuzz_0)"
+        )]
+        target: String,
+        #[arg(required = true, help = "Path to the crash found during fuzzing")]
+        crash_file_path: String,
+        #[arg(
+            short,
+            long,
+            required = false,
+            help = "Generate a regression test executing the same transactions without the fuzzer."
+        )]
+        emit_test: bool,
+    },
     #[command(
         about = "Debug found crash using the AFL on desired fuzz test.",
         override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
@@ -78,7 +293,7 @@ pub enum FuzzCommand {
             \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
             \n\n\x1b[1m\x1b[4mHINT:\x1b[0m By default crashfiles will be stored in the following folders:\
             \n      \x1b[1m\x1b[4mHonggfuzz:\x1b[0m trident-tests/fuzzing/honggfuzz/hfuzz_workspace/<TARGET>\
-            \n      \x1b[1m\x1b[4mAFL:\x1b[0m trident-tests/fuzzing/afl/afl_workspace/out/default/crashes\
+            \n      \x1b[1m\x1b[4mAFL:\x1b[0m trident-tests/fuzzing/afl/afl_workspace/out/<INSTANCE>/crashes\
             \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
             \n      trident fuzz debug-afl fuzz_0 trident-tests/fuzzing/afl/afl_workspace/out/default/crashes/id...\
             \n\n\x1b[1m\x1b[33mWarning\x1b[0m:\
@@ -93,6 +308,27 @@ pub enum FuzzCommand {
         #[arg(required = true, help = "Path to the crash found during fuzzing")]
         crash_file_path: String,
     },
+    #[command(
+        about = "Debug found crash using the libFuzzer on desired fuzz test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to debug (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m By default crashfiles will be stored in the following folders:\
+            \n      \x1b[1m\x1b[4mlibFuzzer:\x1b[0m trident-tests/fuzzing/libfuzzer/libfuzzer_workspace/artifacts\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz debug-libfuzzer fuzz_0 trident-tests/fuzzing/libfuzzer/libfuzzer_workspace/artifacts/crash-...\
+            \n\n\x1b[1m\x1b[33mWarning\x1b[0m:\
+            \n      Do not mix fuzz templates and crashfiles. If the crash was found with fuzz_0, then debug it with fuzz_0."
+    )]
+    Debug_Libfuzzer {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to execute (for example fuzz_0)"
+        )]
+        target: String,
+        #[arg(required = true, help = "Path to the crash found during fuzzing")]
+        crash_file_path: String,
+    },
     #[command(
         about = "Debug found crash using the Honggfuzz on desired fuzz test.",
         override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
@@ -128,25 +364,101 @@ pub async fn fuzz(subcmd: FuzzCommand) {
     let commander = Commander::with_root(&Path::new(&root).to_path_buf());
 
     match subcmd {
-        FuzzCommand::Run_Afl { target } => {
-            commander.run_afl(target).await?;
+        FuzzCommand::Run_Afl {
+            target,
+            stats_format,
+            stats_output,
+            stats_cumulative,
+        } => {
+            let stats_export = stats_format.map(|format| StatsExport {
+                format,
+                output: stats_output,
+                cumulative: stats_cumulative,
+            });
+            commander.run_afl(target, stats_export).await?;
         }
         FuzzCommand::Run_Hfuzz {
             target,
             with_exit_code,
+            stats_format,
+            stats_output,
+            stats_cumulative,
         } => {
+            let stats_export = stats_format.map(|format| StatsExport {
+                format,
+                output: stats_output,
+                cumulative: stats_cumulative,
+            });
             if with_exit_code {
-                commander.run_honggfuzz_with_exit_code(target).await?;
+                commander
+                    .run_honggfuzz_with_exit_code(target, stats_export)
+                    .await?;
             } else {
-                commander.run_honggfuzz(target).await?;
+                commander.run_honggfuzz(target, stats_export).await?;
             }
         }
+        FuzzCommand::Run_Libfuzzer {
+            target,
+            stats_format,
+            stats_output,
+            stats_cumulative,
+        } => {
+            let stats_export = stats_format.map(|format| StatsExport {
+                format,
+                output: stats_output,
+                cumulative: stats_cumulative,
+            });
+            commander.run_libfuzzer(target, stats_export).await?;
+        }
+        FuzzCommand::Minimize {
+            target,
+            afl,
+            libfuzzer,
+        } => {
+            if afl {
+                commander.run_afl_minimize(target).await?;
+            } else if libfuzzer {
+                commander.run_libfuzzer_minimize(target).await?;
+            } else {
+                commander.run_honggfuzz_minimize(target).await?;
+            }
+        }
+        FuzzCommand::Triage { target, afl } => {
+            commander.run_triage(target, afl).await?;
+        }
+        FuzzCommand::Stats { target } => {
+            commander.run_stats(target).await?;
+        }
+        FuzzCommand::Coverage {
+            target,
+            corpus,
+            timeout,
+        } => {
+            commander.run_coverage(target, corpus, timeout).await?;
+        }
+        FuzzCommand::Reproduce {
+            target,
+            crash_file_path,
+            emit_test,
+        } => {
+            commander
+                .run_reproduce(target, crash_file_path, emit_test)
+                .await?;
+        }
         FuzzCommand::Debug_Afl {
             target,
             crash_file_path,
         } => {
             commander.run_afl_debug(target, crash_file_path).await?;
         }
+        FuzzCommand::Debug_Libfuzzer {
+            target,
+            crash_file_path,
+        } => {
+            commander
+                .run_libfuzzer_debug(target, crash_file_path)
+                .await?;
+        }
         FuzzCommand::Debug_Hfuzz {
             target,
             crash_file_path,
@@ -74,7 +74,9 @@ enum Command {
         #[clap(subcommand)]
         subcmd: FuzzCommand,
     },
-    #[command(about = "Clean Honggfuzz build targets ,additionally perform `anchor clean`")]
+    #[command(
+        about = "Clean Honggfuzz, AFL and libFuzzer build targets ,additionally perform `anchor clean`"
+    )]
     Clean,
 }
 
@@ -83,6 +85,7 @@ pub async fn start() {
     let cli = Cli::parse();
 
     match (cli.version, cli.command) {
+        #[allow(clippy::print_literal)]
         (true, _) => {
             println!(
                 "{} - {} \n{}",
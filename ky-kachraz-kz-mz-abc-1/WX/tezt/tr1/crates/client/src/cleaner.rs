@@ -40,6 +40,7 @@ impl Cleaner {
         self.clean_anchor_target().await?;
         self.clean_hfuzz_target(&root).await?;
         self.clean_afl_target(&root).await?;
+        self.clean_libfuzzer_target(&root).await?;
     }
 
     #[throws]
@@ -72,6 +73,19 @@ impl Cleaner {
             )
         }
     }
+
+    #[throws]
+    async fn clean_libfuzzer_target(&self, root: &PathBuf) {
+        let libfuzzer_target_path = Path::new(root).join(CARGO_TARGET_DIR_DEFAULT_LIBFUZZER);
+        if libfuzzer_target_path.exists() {
+            fs::remove_dir_all(libfuzzer_target_path).await?;
+        } else {
+            println!(
+                "{SKIP} [{}] directory not found",
+                CARGO_TARGET_DIR_DEFAULT_LIBFUZZER
+            )
+        }
+    }
 }
 
 /// Tries to find the root directory with the `Anchor.toml` file.
@@ -2,19 +2,24 @@ use crate::constants::*;
 use fehler::{throw, throws};
 use std::io::{Read, Write};
 use std::process::Stdio;
-use std::{fs::File, path::Path};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
 use tokio::{io::AsyncWriteExt, process::Command};
 
 use trident_config::afl::AflSeed;
 use trident_config::TridentConfig;
+use trident_fuzz::replay_log::TRIDENT_REPLAY_LOG;
 
-use super::{Commander, Error};
+use super::dashboard::{CrashLocation, Dashboard};
+use super::{get_afl_instance_dirs, get_corpus_size, Commander, Error, StatsExport};
 use rand::RngCore;
 
 impl Commander {
     /// Runs fuzzer on the given target.
     #[throws]
-    pub async fn run_afl(&self, target: String) {
+    pub async fn run_afl(&self, target: String, stats_export: Option<StatsExport>) {
         let config = TridentConfig::new();
 
         // build args without cargo target dir
@@ -62,16 +67,157 @@ impl Commander {
             .spawn()?;
         Self::handle_child(&mut child).await?;
 
+        let instances = config.get_afl_instances();
+        // exporting the statistics implies collecting them
+        let with_stats = config.get_fuzzing_with_stats() || stats_export.is_some();
+
+        // all instances share the output directory, the first one is the main instance
+        // and the others are secondary instances synchronizing with it
+        let mut children = vec![];
+        for instance in 0..instances {
+            let mut command = Command::new("cargo");
+            command
+                .arg("afl")
+                .arg("fuzz")
+                .args(["-i", &afl_workspace_in])
+                .args(["-o", &afl_workspace_out]);
+
+            if instances > 1 {
+                if instance == 0 {
+                    command.args(["-M", AFL_MAIN_INSTANCE]);
+                } else {
+                    command.args(["-S", &format!("{}_{}", AFL_SECONDARY_INSTANCE, instance)]);
+                }
+            }
+
+            command
+                .args(&fuzz_args)
+                .arg(&full_target_path)
+                .kill_on_drop(true);
+
+            if with_stats {
+                // the statistics are printed by the fuzzed binary, so its output
+                // is enabled and the status screen is disabled
+                command
+                    .env("FUZZING_METRICS", "1")
+                    .env("AFL_DEBUG_CHILD", "1")
+                    .env("AFL_NO_UI", "1")
+                    .stdout(Stdio::piped());
+            } else if instance > 0 {
+                // only the main instance shows the status screen
+                command.env("AFL_NO_UI", "1").stdout(Stdio::null());
+            }
+
+            children.push(command.spawn()?);
+        }
+
+        if with_stats {
+            let dashboard = config.get_live_stats().then(|| {
+                Dashboard::new(
+                    &target,
+                    CrashLocation::Afl {
+                        afl_workspace_out: PathBuf::from(&afl_workspace_out),
+                    },
+                )
+            });
+            self.handle_children_with_stats(
+                &mut children,
+                &target,
+                dashboard,
+                stats_export.as_ref(),
+            )
+            .await?;
+        } else {
+            Self::handle_children(&mut children).await?;
+        }
+    }
+
+    /// Minimizes the AFL queue of the given target, keeping only the inputs which
+    /// contribute new coverage. The minimized corpus is stored next to the AFL
+    /// output directory so it can be used as the input of the next campaign.
+    #[throws]
+    pub async fn run_afl_minimize(&self, target: String) {
+        let config = TridentConfig::new();
+
+        // build args without cargo target dir
+        let build_args = config.get_afl_build_args();
+
+        // cargo target directory
+        let cargo_target_dir = config.get_afl_target_dir();
+
+        let afl_workspace_out = config.get_afl_workspace_out();
+
+        let full_target_path = config.get_afl_target_path(&target);
+
+        let queue_dirs: Vec<PathBuf> = get_afl_instance_dirs(Path::new(&afl_workspace_out))
+            .iter()
+            .map(|instance_dir| instance_dir.join(AFL_QUEUE_DIRECTORY))
+            .collect();
+
+        let corpus_size: usize = queue_dirs.iter().map(|dir| get_corpus_size(dir)).sum();
+        if corpus_size == 0 {
+            println!(
+                "{ERROR} The AFL queue in [{}] not found or empty",
+                afl_workspace_out
+            );
+            throw!(Error::CorpusNotFound);
+        }
+
+        let minimized_dir = Path::new(&afl_workspace_out).with_file_name(AFL_MINIMIZED_DIRECTORY);
+
+        // afl-cmin accepts a single input directory, so the queues of multiple
+        // instances are merged first
+        let input_dir = match queue_dirs.as_slice() {
+            [queue_dir] => queue_dir.clone(),
+            _ => {
+                let merged_dir =
+                    Path::new(&afl_workspace_out).with_file_name(AFL_MERGED_QUEUE_DIRECTORY);
+                merge_queues(&queue_dirs, &merged_dir)?;
+                merged_dir
+            }
+        };
+
+        // afl-cmin requires the output directory to be empty
+        if minimized_dir.exists() {
+            std::fs::remove_dir_all(&minimized_dir)?;
+        }
+
+        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+
+        rustflags.push_str("--cfg afl");
+
         let mut child = Command::new("cargo")
+            .env("RUSTFLAGS", rustflags)
             .arg("afl")
-            .arg("fuzz")
-            .args(["-i", &afl_workspace_in])
-            .args(["-o", &afl_workspace_out])
-            .args(fuzz_args)
-            .arg(&full_target_path)
+            .arg("build")
+            .args(["--target-dir", &cargo_target_dir])
+            .args(build_args)
+            .args(["--bin", &target])
             .spawn()?;
+        Self::handle_child(&mut child).await?;
 
+        let mut child = Command::new("cargo")
+            .arg("afl")
+            .arg("cmin")
+            .arg("-i")
+            .arg(&input_dir)
+            .arg("-o")
+            .arg(&minimized_dir)
+            .arg("--")
+            .arg(&full_target_path)
+            .spawn()?;
         Self::handle_child(&mut child).await?;
+
+        if input_dir.ends_with(AFL_MERGED_QUEUE_DIRECTORY) {
+            std::fs::remove_dir_all(&input_dir)?;
+        }
+
+        println!(
+            "{FINISH} [{}] minimized from {} to {} inputs",
+            minimized_dir.display(),
+            corpus_size,
+            get_corpus_size(&minimized_dir)
+        );
     }
 
     /// Runs fuzzer on the given target.
@@ -105,9 +251,13 @@ impl Commander {
         let mut file_contents = Vec::new();
         file.read_to_end(&mut file_contents)?;
 
+        // the replay log is stored next to the crash file
+        let replay_log = format!("{}.{}", crash_file.display(), REPLAY_LOG_EXTENSION);
+
         // using exec rather than spawn and replacing current process to avoid unflushed terminal output after ctrl+c signal
         let mut child = Command::new("cargo")
             .env("TRIDENT_LOG", "1")
+            .env(TRIDENT_REPLAY_LOG, replay_log)
             .env("RUSTFLAGS", rustflags)
             .arg("afl")
             .arg("run")
@@ -123,6 +273,38 @@ impl Commander {
     }
 }
 
+/// Copies the queues of all instances into a single directory, the file names are
+/// prefixed with the instance name as the queue entries of the instances clash.
+fn merge_queues(queue_dirs: &[PathBuf], merged_dir: &Path) -> std::io::Result<()> {
+    if merged_dir.exists() {
+        std::fs::remove_dir_all(merged_dir)?;
+    }
+    std::fs::create_dir_all(merged_dir)?;
+
+    for queue_dir in queue_dirs {
+        let instance = queue_dir
+            .parent()
+            .and_then(|instance_dir| instance_dir.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        for entry in std::fs::read_dir(queue_dir)?.filter_map(|res| res.ok()) {
+            let path = entry.path();
+            if path.is_file() {
+                std::fs::copy(
+                    &path,
+                    merged_dir.join(format!(
+                        "{}_{}",
+                        instance,
+                        entry.file_name().to_string_lossy()
+                    )),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn create_seed_file(path: &Path, seed: &AflSeed) -> std::io::Result<()> {
     let (bytes, override_file) = obtain_seed(seed);
 
@@ -0,0 +1,404 @@
+use fehler::{throw, throws};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::time::Instant;
+
+use trident_config::TridentConfig;
+use trident_fuzz::coverage::REPLAYED_INPUT_MARKER;
+
+use crate::constants::*;
+
+use super::libfuzzer::get_host_triple;
+use super::{get_afl_crash_files, get_afl_instance_dirs, Commander, Error};
+
+impl Commander {
+    /// Replays the corpus of the given target with the fuzz test built with the source-based
+    /// coverage and generates the HTML and lcov reports of the programs under test. The inputs
+    /// which abort the replay or do not finish within `timeout` seconds are reported and skipped.
+    #[throws]
+    pub async fn run_coverage(&self, target: String, corpus_dir: Option<PathBuf>, timeout: u64) {
+        let config = TridentConfig::new();
+
+        let mut inputs = match &corpus_dir {
+            Some(corpus_dir) => get_corpus_files(corpus_dir),
+            None => get_saved_corpus(&config, &target),
+        };
+        if inputs.is_empty() {
+            match corpus_dir {
+                Some(corpus_dir) => println!(
+                    "{ERROR} The corpus directory [{}] not found or empty",
+                    corpus_dir.display()
+                ),
+                None => println!(
+                    "{ERROR} No corpus of [{}] found, run the fuzz test first or provide the corpus directory",
+                    target
+                ),
+            }
+            throw!(Error::CorpusNotFound);
+        }
+        inputs.sort();
+
+        let host_triple = get_host_triple().await?;
+        let llvm_profdata = get_llvm_tool("llvm-profdata", &host_triple).await?;
+        let llvm_cov = get_llvm_tool("llvm-cov", &host_triple).await?;
+
+        let coverage_dir = self.root.join(COVERAGE_DIRECTORY).join(&target);
+        let profraw_dir = coverage_dir.join(COVERAGE_PROFRAW_DIRECTORY);
+
+        // the profiles of the previous runs would be merged into the report otherwise
+        if profraw_dir.exists() {
+            std::fs::remove_dir_all(&profraw_dir)?;
+        }
+        std::fs::create_dir_all(&profraw_dir)?;
+
+        // cargo target directory, separate from the fuzzers as the instrumentation differs
+        let cargo_target_dir = self.root.join(CARGO_TARGET_DIR_DEFAULT_COVERAGE);
+
+        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+
+        rustflags.push_str(COVERAGE_RUSTFLAGS);
+
+        // the instrumentation must not be applied to the build scripts and proc macros,
+        // which is the case only if the target is passed explicitly
+        let mut child = Command::new("cargo")
+            .env("RUSTFLAGS", rustflags)
+            .arg("build")
+            .args(["--target", &host_triple])
+            .arg("--target-dir")
+            .arg(&cargo_target_dir)
+            .args(["--bin", &target])
+            .spawn()?;
+        Self::handle_child(&mut child).await?;
+
+        let binary = cargo_target_dir
+            .join(&host_triple)
+            .join("debug")
+            .join(&target);
+
+        println!("Replaying {} inputs of [{}]", inputs.len(), target);
+
+        let timeout = Duration::from_secs(timeout);
+
+        let mut aborted = vec![];
+        let mut timed_out = vec![];
+
+        // the coverage of a child is written only if it exits, so the inputs replayed
+        // before the one which aborted or hung are replayed again in the next child
+        let mut pending = inputs;
+        while !pending.is_empty() {
+            match replay_inputs(&binary, &profraw_dir, &target, &pending, timeout).await? {
+                ReplayOutcome::Finished => break,
+                ReplayOutcome::Aborted { index, stderr } => {
+                    let input = pending.remove(index);
+                    println!(
+                        "{WARNING} Input [{}] aborted the replay{}",
+                        input.display(),
+                        stderr
+                    );
+                    aborted.push(input);
+                }
+                ReplayOutcome::TimedOut { index } => {
+                    let input = pending.remove(index);
+                    println!(
+                        "{WARNING} Input [{}] did not finish within {} seconds",
+                        input.display(),
+                        timeout.as_secs()
+                    );
+                    timed_out.push(input);
+                }
+            }
+        }
+
+        if !aborted.is_empty() || !timed_out.is_empty() {
+            println!(
+                "{WARNING} {} inputs aborted and {} inputs timed out, their coverage is not reported",
+                aborted.len(),
+                timed_out.len()
+            );
+        }
+
+        let profraw_files = get_corpus_files(&profraw_dir);
+        if profraw_files.is_empty() {
+            println!(
+                "{ERROR} No coverage data found in [{}], the replay of the corpus aborted",
+                profraw_dir.display()
+            );
+            throw!(Error::CoverageNotCollected);
+        }
+
+        let profdata = coverage_dir.join(COVERAGE_PROFDATA_FILE);
+
+        let mut command = Command::new(&llvm_profdata);
+        command
+            .arg("merge")
+            .arg("-sparse")
+            .args(&profraw_files)
+            .arg("-o")
+            .arg(&profdata);
+        run_llvm_tool(&mut command).await?;
+
+        // only the programs are reported, without the fuzz tests, Trident, the SVM
+        // and other dependencies, which are located outside of the project
+        let ignore_filename_regex = format!(
+            "^{}/|/\\.cargo/|/\\.rustup/|^/rustc/",
+            escape_regex(&self.root.join(TESTS_WORKSPACE_DIRECTORY).to_string_lossy())
+        );
+        let llvm_cov_args = |subcommand: &str| -> Vec<String> {
+            vec![
+                subcommand.to_string(),
+                binary.to_string_lossy().to_string(),
+                format!("-instr-profile={}", profdata.display()),
+                format!("-ignore-filename-regex={}", ignore_filename_regex),
+            ]
+        };
+
+        let html_dir = coverage_dir.join(COVERAGE_HTML_DIRECTORY);
+
+        let mut command = Command::new(&llvm_cov);
+        command
+            .args(llvm_cov_args("show"))
+            .arg("-format=html")
+            .arg("-show-branches=count")
+            .arg(format!("-output-dir={}", html_dir.display()))
+            .arg(&self.root);
+        run_llvm_tool(&mut command).await?;
+
+        let lcov_file = coverage_dir.join(COVERAGE_LCOV_FILE);
+
+        let mut command = Command::new(&llvm_cov);
+        command
+            .args(llvm_cov_args("export"))
+            .arg("-format=lcov")
+            .arg(&self.root)
+            .stdout(std::fs::File::create(&lcov_file)?);
+        run_llvm_tool(&mut command).await?;
+
+        let mut command = Command::new(&llvm_cov);
+        command.args(llvm_cov_args("report")).arg(&self.root);
+        run_llvm_tool(&mut command).await?;
+
+        println!(
+            "{FINISH} Coverage report of [{}] saved to [{}] and [{}]",
+            target,
+            html_dir.join("index.html").display(),
+            lcov_file.display()
+        );
+    }
+}
+
+/// Number of the last stderr lines shown for the input which aborted the replay.
+const STDERR_TAIL_LINES: usize = 10;
+
+/// Result of replaying the inputs in a single child process.
+enum ReplayOutcome {
+    /// All inputs were replayed and the coverage was written.
+    Finished,
+    /// The input at the index aborted the child, with the last lines of its stderr.
+    Aborted { index: usize, stderr: String },
+    /// The input at the index did not finish within the timeout, the child was killed.
+    TimedOut { index: usize },
+}
+
+/// Replays the inputs in a new child process, which reports every replayed input on stdout.
+/// Stops at the first input which aborts the child or does not finish within the timeout.
+#[throws]
+async fn replay_inputs(
+    binary: &Path,
+    profraw_dir: &Path,
+    target: &str,
+    inputs: &[PathBuf],
+    timeout: Duration,
+) -> ReplayOutcome {
+    let mut child = Command::new(binary)
+        .env(
+            "LLVM_PROFILE_FILE",
+            profraw_dir.join(format!("{}-%p-%m.profraw", target)),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let inputs_list = inputs
+        .iter()
+        .map(|input| input.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut stdin = child.stdin.take().expect("stdin of the child is piped");
+    // written concurrently, the child may block on a full stdout pipe otherwise;
+    // stdin is closed once dropped, so the fuzz test stops reading the inputs
+    let writer = tokio::spawn(async move {
+        let _ = stdin.write_all(inputs_list.as_bytes()).await;
+    });
+
+    // the output of the fuzz test could fill the pipe and block the child
+    let stderr = child.stderr.take().expect("stderr of the child is piped");
+    let stderr_reader = tokio::spawn(async move {
+        let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tail.len() == STDERR_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+        tail
+    });
+
+    let stdout = child.stdout.take().expect("stdout of the child is piped");
+    let mut lines = BufReader::new(stdout).lines();
+
+    let mut replayed = 0;
+    // other output of the fuzz test does not extend the deadline of the input
+    let mut deadline = Instant::now() + timeout;
+    while replayed < inputs.len() {
+        match tokio::time::timeout_at(deadline, lines.next_line()).await {
+            Ok(Ok(Some(line))) => {
+                if line == REPLAYED_INPUT_MARKER {
+                    replayed += 1;
+                    deadline = Instant::now() + timeout;
+                }
+            }
+            // the child exited before all inputs were replayed
+            Ok(_) => break,
+            Err(_) => {
+                child.kill().await?;
+                writer.abort();
+                return ReplayOutcome::TimedOut { index: replayed };
+            }
+        }
+    }
+
+    let pid = child.id();
+    let status = child.wait().await?;
+    let _ = writer.await;
+    let tail = stderr_reader.await.unwrap_or_default();
+
+    if replayed == inputs.len() {
+        ReplayOutcome::Finished
+    } else {
+        // the inputs are replayed again, their coverage would be counted twice if
+        // the child wrote its profile, e.g. when exited by the program under test
+        if let Some(pid) = pid {
+            let prefix = format!("{}-{}-", target, pid);
+            for profraw_file in get_corpus_files(profraw_dir) {
+                if profraw_file
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+                {
+                    std::fs::remove_file(profraw_file)?;
+                }
+            }
+        }
+
+        let mut stderr = format!(" ({})", status);
+        for line in tail {
+            stderr.push_str("\n    ");
+            stderr.push_str(&line);
+        }
+        ReplayOutcome::Aborted {
+            index: replayed,
+            stderr,
+        }
+    }
+}
+
+/// Collects the corpora saved by all fuzzers. The corpora of AFL and libFuzzer
+/// are shared by all fuzz tests of the project.
+fn get_saved_corpus(config: &TridentConfig, target: &str) -> Vec<PathBuf> {
+    let hfuzz_workspace =
+        std::env::var("HFUZZ_WORKSPACE").unwrap_or_else(|_| config.get_honggfuzz_workspace());
+
+    // honggfuzz-rs reads the corpus from HFUZZ_INPUT, which defaults to the input
+    // folder of the target inside of the workspace
+    let hfuzz_input = std::env::var("HFUZZ_INPUT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            Path::new(&hfuzz_workspace)
+                .join(target)
+                .join(HFUZZ_INPUT_DIRECTORY)
+        });
+
+    let mut inputs = get_corpus_files(&hfuzz_input);
+
+    // the queue entries are named the same way as the crashes
+    inputs.extend(
+        get_afl_instance_dirs(Path::new(&config.get_afl_workspace_out()))
+            .iter()
+            .flat_map(|instance_dir| get_afl_crash_files(&instance_dir.join(AFL_QUEUE_DIRECTORY))),
+    );
+
+    inputs.extend(get_corpus_files(Path::new(
+        &config.get_libfuzzer_workspace_corpus(),
+    )));
+
+    inputs
+}
+
+/// Returns the files within the given directory.
+fn get_corpus_files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|res| res.ok())
+                .map(|dir_entry| dir_entry.path())
+                .filter(|path| path.is_file())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Path to the LLVM tool of the `llvm-tools` rustup component, which matches the LLVM
+/// version of rustc. Falls back to the tool on the PATH if the component is not installed.
+#[throws]
+async fn get_llvm_tool(name: &str, host_triple: &str) -> PathBuf {
+    let output = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .await?;
+    let sysroot = String::from_utf8(output.stdout)?;
+
+    let tool = Path::new(sysroot.trim())
+        .join("lib")
+        .join("rustlib")
+        .join(host_triple)
+        .join("bin")
+        .join(name);
+
+    if tool.is_file() {
+        tool
+    } else {
+        PathBuf::from(name)
+    }
+}
+
+/// Runs the LLVM tool and waits for it to finish.
+#[throws]
+async fn run_llvm_tool(command: &mut Command) {
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => throw!(Error::LlvmToolsNotFound),
+        Err(e) => throw!(e),
+    };
+    if !child.wait().await?.success() {
+        throw!(Error::CoverageReportFailed);
+    }
+}
+
+/// Escapes the characters with a special meaning in the regular expressions.
+fn escape_regex(value: &str) -> String {
+    value
+        .chars()
+        .fold(String::with_capacity(value.len()), |mut escaped, c| {
+            if "\\.+*?()|[]{}^$".contains(c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        })
+}
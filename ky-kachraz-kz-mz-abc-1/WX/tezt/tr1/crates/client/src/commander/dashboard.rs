@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use trident_fuzz::fuzz_stats::FuzzingStatistics;
+
+use crate::constants::*;
+
+use super::{
+    get_afl_crash_files, get_afl_instance_dirs, get_crash_files, get_libfuzzer_crash_files,
+};
+
+/// Location of the crash files counted by the live dashboard.
+pub(super) enum CrashLocation {
+    Honggfuzz {
+        crash_dir: PathBuf,
+        extension: String,
+    },
+    Afl {
+        afl_workspace_out: PathBuf,
+    },
+    LibFuzzer {
+        artifacts_dir: PathBuf,
+    },
+}
+
+impl CrashLocation {
+    fn count(&self) -> usize {
+        match self {
+            CrashLocation::Honggfuzz {
+                crash_dir,
+                extension,
+            } => get_crash_files(crash_dir, extension)
+                .map(|crash_files| crash_files.len())
+                .unwrap_or_default(),
+            CrashLocation::Afl { afl_workspace_out } => get_afl_instance_dirs(afl_workspace_out)
+                .iter()
+                .map(|instance_dir| {
+                    get_afl_crash_files(&instance_dir.join(AFL_CRASHES_DIRECTORY)).len()
+                })
+                .sum(),
+            CrashLocation::LibFuzzer { artifacts_dir } => {
+                get_libfuzzer_crash_files(artifacts_dir).len()
+            }
+        }
+    }
+}
+
+/// Live status screen showing the per-transaction statistics of the running fuzz test,
+/// which the status screens of the fuzzers cannot show.
+pub(super) struct Dashboard {
+    target: String,
+    crash_location: CrashLocation,
+    start: Instant,
+    initial_crashes: usize,
+    crashes: usize,
+    last_new_crash: Option<Instant>,
+    last_invoked: Option<u64>,
+    last_iterations: Option<u64>,
+    last_refresh: Instant,
+}
+
+impl Dashboard {
+    pub(super) fn new(target: &str, crash_location: CrashLocation) -> Self {
+        let crashes = crash_location.count();
+        Self {
+            target: target.to_string(),
+            crash_location,
+            start: Instant::now(),
+            initial_crashes: crashes,
+            crashes,
+            last_new_crash: None,
+            last_invoked: None,
+            last_iterations: None,
+            last_refresh: Instant::now(),
+        }
+    }
+
+    /// Redraws the dashboard with the current statistics.
+    pub(super) fn refresh(&mut self, stats: &FuzzingStatistics) {
+        let now = Instant::now();
+
+        let crashes = self.crash_location.count();
+        if crashes > self.crashes {
+            self.last_new_crash = Some(now);
+        }
+        self.crashes = crashes;
+
+        // the statistics include the previous sessions, so only the difference is measured
+        let elapsed = now.duration_since(self.last_refresh).as_secs_f64();
+        let executions_per_second =
+            per_second(stats.iterations, &mut self.last_iterations, elapsed);
+        let transactions_per_second =
+            per_second(stats.total_invoked(), &mut self.last_invoked, elapsed);
+        self.last_refresh = now;
+
+        let last_new_crash = match self.last_new_crash {
+            Some(last_new_crash) => format!(
+                "{} ago",
+                format_duration(now.duration_since(last_new_crash).as_secs())
+            ),
+            None => "none yet".to_string(),
+        };
+
+        // clear the screen and move the cursor to the top left corner
+        print!("\x1b[2J\x1b[H");
+        println!(
+            "\x1b[96mTrident\x1b[0m \x1b[1m{}\x1b[0m (press Ctrl+C to stop)",
+            self.target
+        );
+        println!(
+            "  run time         : {}",
+            format_duration(now.duration_since(self.start).as_secs())
+        );
+        println!("  executions/sec   : {}", executions_per_second);
+        println!("  transactions/sec : {}", transactions_per_second);
+        println!(
+            "  crashes          : {} ({} new)",
+            self.crashes,
+            self.crashes.saturating_sub(self.initial_crashes)
+        );
+        println!("  last new crash   : {}", last_new_crash);
+        stats.table().printstd();
+    }
+}
+
+/// Rate of the counter since the last refresh, the counter is updated to the current value.
+fn per_second(current: u64, last: &mut Option<u64>, elapsed: f64) -> u64 {
+    let rate = match *last {
+        Some(last) if elapsed > 0.0 => {
+            (current.saturating_sub(last) as f64 / elapsed).round() as u64
+        }
+        _ => 0,
+    };
+    *last = Some(current);
+    rate
+}
+
+fn format_duration(seconds: u64) -> String {
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
@@ -5,15 +5,23 @@ use std::process::Stdio;
 use tokio::process::Command;
 
 use trident_config::TridentConfig;
+use trident_fuzz::replay_log::TRIDENT_REPLAY_LOG;
 
 use crate::constants::*;
 
-use super::{get_crash_dir_and_ext, get_crash_files, Commander, Error};
+use super::dashboard::{CrashLocation, Dashboard};
+use super::{
+    get_corpus_size, get_crash_dir_and_ext, get_crash_files, Commander, Error, StatsExport,
+};
 
 impl Commander {
     /// Runs fuzzer on the given target with exit code option.
     #[throws]
-    pub async fn run_honggfuzz_with_exit_code(&self, target: String) {
+    pub async fn run_honggfuzz_with_exit_code(
+        &self,
+        target: String,
+        stats_export: Option<StatsExport>,
+    ) {
         let config = TridentConfig::new();
 
         // obtain hfuzz_run_args from env variable, this variable can contain multiple
@@ -42,11 +50,25 @@ impl Commander {
             }
         }
 
-        match config.get_fuzzing_with_stats() {
+        // exporting the statistics implies collecting them
+        match config.get_fuzzing_with_stats() || stats_export.is_some() {
             true => {
                 // enforce keep output to be true
                 fuzz_args.push_str("--keep_output");
                 std::env::set_var("FUZZING_METRICS", "1");
+                let dashboard = if config.get_live_stats() {
+                    // the honggfuzz status screen would overwrite the dashboard
+                    fuzz_args.push_str(&honggfuzz_logfile_arg(&hfuzz_workspace, &target)?);
+                    Some(Dashboard::new(
+                        &target,
+                        CrashLocation::Honggfuzz {
+                            crash_dir: crash_dir.clone(),
+                            extension: ext.clone(),
+                        },
+                    ))
+                } else {
+                    None
+                };
 
                 let mut child = Command::new("cargo")
                     .env("HFUZZ_RUN_ARGS", fuzz_args)
@@ -55,10 +77,11 @@ impl Commander {
                     .env("RUSTFLAGS", rustflags)
                     .arg("hfuzz")
                     .arg("run")
-                    .arg(target)
+                    .arg(&target)
                     .stdout(Stdio::piped())
                     .spawn()?;
-                Self::handle_child_with_stats(&mut child).await?;
+                self.handle_child_with_stats(&mut child, &target, dashboard, stats_export.as_ref())
+                    .await?;
             }
             false => {
                 let mut child = Command::new("cargo")
@@ -87,7 +110,7 @@ impl Commander {
 
     /// Runs fuzzer on the given target.
     #[throws]
-    pub async fn run_honggfuzz(&self, target: String) {
+    pub async fn run_honggfuzz(&self, target: String, stats_export: Option<StatsExport>) {
         let config = TridentConfig::new();
 
         let hfuzz_run_args = std::env::var("HFUZZ_RUN_ARGS").unwrap_or_default();
@@ -103,11 +126,27 @@ impl Commander {
 
         rustflags.push_str("--cfg honggfuzz");
 
-        match config.get_fuzzing_with_stats() {
+        // exporting the statistics implies collecting them
+        match config.get_fuzzing_with_stats() || stats_export.is_some() {
             true => {
                 // enforce keep output to be true
                 std::env::set_var("FUZZING_METRICS", "1");
                 fuzz_args.push_str("--keep_output");
+                let dashboard = if config.get_live_stats() {
+                    let (crash_dir, extension) =
+                        get_crash_dir_and_ext(&self.root, &target, &fuzz_args, &hfuzz_workspace);
+                    // the honggfuzz status screen would overwrite the dashboard
+                    fuzz_args.push_str(&honggfuzz_logfile_arg(&hfuzz_workspace, &target)?);
+                    Some(Dashboard::new(
+                        &target,
+                        CrashLocation::Honggfuzz {
+                            crash_dir,
+                            extension,
+                        },
+                    ))
+                } else {
+                    None
+                };
                 let mut child = Command::new("cargo")
                     .env("HFUZZ_RUN_ARGS", fuzz_args)
                     .env("CARGO_TARGET_DIR", cargo_target_dir)
@@ -115,10 +154,11 @@ impl Commander {
                     .env("RUSTFLAGS", rustflags)
                     .arg("hfuzz")
                     .arg("run")
-                    .arg(target)
+                    .arg(&target)
                     .stdout(Stdio::piped())
                     .spawn()?;
-                Self::handle_child_with_stats(&mut child).await?;
+                self.handle_child_with_stats(&mut child, &target, dashboard, stats_export.as_ref())
+                    .await?;
             }
             false => {
                 let mut child = Command::new("cargo")
@@ -134,6 +174,66 @@ impl Commander {
             }
         }
     }
+    /// Minimizes the Honggfuzz corpus of the given target in place, keeping only
+    /// the inputs which contribute new coverage.
+    #[throws]
+    pub async fn run_honggfuzz_minimize(&self, target: String) {
+        let config = TridentConfig::new();
+
+        let hfuzz_run_args = std::env::var("HFUZZ_RUN_ARGS").unwrap_or_default();
+
+        let cargo_target_dir =
+            std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| config.get_honggfuzz_target_dir());
+        let hfuzz_workspace =
+            std::env::var("HFUZZ_WORKSPACE").unwrap_or_else(|_| config.get_honggfuzz_workspace());
+
+        // honggfuzz-rs reads the corpus from HFUZZ_INPUT, which defaults to the input
+        // folder of the target inside of the workspace
+        let corpus_dir = std::env::var("HFUZZ_INPUT").unwrap_or_else(|_| {
+            Path::new(&hfuzz_workspace)
+                .join(&target)
+                .join(HFUZZ_INPUT_DIRECTORY)
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let corpus_size = get_corpus_size(Path::new(&corpus_dir));
+        if corpus_size == 0 {
+            println!(
+                "{ERROR} The corpus directory [{}] not found or empty",
+                corpus_dir
+            );
+            throw!(Error::CorpusNotFound);
+        }
+
+        // the inputs are executed with the same settings as during fuzzing, without the --output
+        // option Honggfuzz removes redundant inputs directly from the input folder
+        let fuzz_args = format!("{} --minimize", config.get_honggfuzz_args(hfuzz_run_args));
+
+        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+
+        rustflags.push_str("--cfg honggfuzz");
+
+        let mut child = Command::new("cargo")
+            .env("HFUZZ_RUN_ARGS", fuzz_args)
+            .env("CARGO_TARGET_DIR", cargo_target_dir)
+            .env("HFUZZ_WORKSPACE", hfuzz_workspace)
+            .env("HFUZZ_INPUT", &corpus_dir)
+            .env("RUSTFLAGS", rustflags)
+            .arg("hfuzz")
+            .arg("run")
+            .arg(target)
+            .spawn()?;
+        Self::handle_child(&mut child).await?;
+
+        println!(
+            "{FINISH} [{}] minimized from {} to {} inputs",
+            corpus_dir,
+            corpus_size,
+            get_corpus_size(Path::new(&corpus_dir))
+        );
+    }
+
     /// Runs fuzzer on the given target.
     #[throws]
     pub async fn run_hfuzz_debug(&self, target: String, crash_file_path: String) {
@@ -162,8 +262,12 @@ impl Commander {
         rustflags.push_str("--cfg honggfuzz_debug --cfg fuzzing_debug");
 
         // using exec rather than spawn and replacing current process to avoid unflushed terminal output after ctrl+c signal
+        // the replay log is stored next to the crash file
+        let replay_log = format!("{}.{}", crash_file.display(), REPLAY_LOG_EXTENSION);
+
         let mut child = tokio::process::Command::new("cargo")
             .env("TRIDENT_LOG", "1")
+            .env(TRIDENT_REPLAY_LOG, replay_log)
             .env("CARGO_TARGET_DIR", cargo_target_dir)
             .env("RUSTFLAGS", rustflags)
             .arg("run")
@@ -180,3 +284,14 @@ impl Commander {
         Self::handle_child(&mut child).await?;
     }
 }
+
+/// Honggfuzz argument redirecting its log into the workspace, which also disables its status screen.
+#[throws]
+fn honggfuzz_logfile_arg(hfuzz_workspace: &str, target: &str) -> String {
+    let logfile_dir = Path::new(hfuzz_workspace).join(target);
+    std::fs::create_dir_all(&logfile_dir)?;
+    format!(
+        " --logfile {}",
+        logfile_dir.join(HONGGFUZZ_LOGFILE).display()
+    )
+}
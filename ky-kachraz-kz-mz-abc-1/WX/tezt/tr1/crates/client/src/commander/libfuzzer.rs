@@ -0,0 +1,204 @@
+use crate::constants::*;
+use fehler::{throw, throws};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+use trident_config::TridentConfig;
+use trident_fuzz::replay_log::TRIDENT_REPLAY_LOG;
+
+use super::dashboard::{CrashLocation, Dashboard};
+use super::{get_corpus_size, Commander, Error, StatsExport};
+
+impl Commander {
+    /// Runs fuzzer on the given target.
+    #[throws]
+    pub async fn run_libfuzzer(&self, target: String, stats_export: Option<StatsExport>) {
+        let config = TridentConfig::new();
+
+        // fuzz args without the corpus and artifacts directories
+        let fuzz_args = config.get_libfuzzer_fuzz_args();
+
+        // cargo target directory
+        let cargo_target_dir = config.get_libfuzzer_target_dir();
+
+        // libfuzzer workspace corpus and artifacts
+        let corpus_dir = config.get_libfuzzer_workspace_corpus();
+        let artifacts_dir = config.get_libfuzzer_workspace_artifacts();
+
+        // the instrumentation must not be applied to the build scripts and proc macros,
+        // which is the case only if the target is passed explicitly
+        let host_triple = get_host_triple().await?;
+
+        let full_target_path = config.get_libfuzzer_target_path(&target, &host_triple);
+
+        std::fs::create_dir_all(&corpus_dir)?;
+        std::fs::create_dir_all(&artifacts_dir)?;
+
+        build_libfuzzer_target(&target, &host_triple, &cargo_target_dir).await?;
+
+        // libFuzzer concatenates the prefix with the name of the artifact
+        let artifact_prefix = format!("-artifact_prefix={}/", artifacts_dir);
+
+        let mut command = Command::new(&full_target_path);
+        command
+            .arg(&artifact_prefix)
+            .args(fuzz_args)
+            .arg(&corpus_dir);
+
+        // exporting the statistics implies collecting them
+        if config.get_fuzzing_with_stats() || stats_export.is_some() {
+            // libFuzzer reports its progress on the standard error, so only the statistics
+            // printed by the fuzzed binary are on the standard output
+            let mut child = command
+                .env("FUZZING_METRICS", "1")
+                .stdout(Stdio::piped())
+                .spawn()?;
+            let dashboard = config.get_live_stats().then(|| {
+                Dashboard::new(
+                    &target,
+                    CrashLocation::LibFuzzer {
+                        artifacts_dir: PathBuf::from(&artifacts_dir),
+                    },
+                )
+            });
+            self.handle_child_with_stats(&mut child, &target, dashboard, stats_export.as_ref())
+                .await?;
+        } else {
+            let mut child = command.spawn()?;
+            Self::handle_child(&mut child).await?;
+        }
+    }
+
+    /// Minimizes the libFuzzer corpus of the given target in place, the inputs are
+    /// merged into an empty directory which then replaces the corpus.
+    #[throws]
+    pub async fn run_libfuzzer_minimize(&self, target: String) {
+        let config = TridentConfig::new();
+
+        // cargo target directory
+        let cargo_target_dir = config.get_libfuzzer_target_dir();
+
+        let corpus_dir = config.get_libfuzzer_workspace_corpus();
+
+        let corpus_size = get_corpus_size(Path::new(&corpus_dir));
+        if corpus_size == 0 {
+            println!(
+                "{ERROR} The corpus directory [{}] not found or empty",
+                corpus_dir
+            );
+            throw!(Error::CorpusNotFound);
+        }
+
+        let host_triple = get_host_triple().await?;
+
+        let full_target_path = config.get_libfuzzer_target_path(&target, &host_triple);
+
+        // the merge requires the output directory to be empty
+        let minimized_dir = Path::new(&corpus_dir).with_file_name(LIBFUZZER_MINIMIZED_DIRECTORY);
+        if minimized_dir.exists() {
+            std::fs::remove_dir_all(&minimized_dir)?;
+        }
+        std::fs::create_dir_all(&minimized_dir)?;
+
+        build_libfuzzer_target(&target, &host_triple, &cargo_target_dir).await?;
+
+        // the inputs are executed with the same settings as during fuzzing
+        let mut child = Command::new(&full_target_path)
+            .arg("-merge=1")
+            .args(config.get_libfuzzer_fuzz_args())
+            .arg(&minimized_dir)
+            .arg(&corpus_dir)
+            .spawn()?;
+        Self::handle_child(&mut child).await?;
+
+        std::fs::remove_dir_all(&corpus_dir)?;
+        std::fs::rename(&minimized_dir, &corpus_dir)?;
+
+        println!(
+            "{FINISH} [{}] minimized from {} to {} inputs",
+            corpus_dir,
+            corpus_size,
+            get_corpus_size(Path::new(&corpus_dir))
+        );
+    }
+
+    /// Runs the given target on the crash file, libFuzzer executes the input
+    /// only once if it is passed as a file instead of the corpus directory.
+    #[throws]
+    pub async fn run_libfuzzer_debug(&self, target: String, crash_file: String) {
+        let config = TridentConfig::new();
+
+        let crash_file_path = Path::new(&crash_file);
+
+        let crash_file = if crash_file_path.is_absolute() {
+            crash_file_path
+        } else {
+            let cwd = std::env::current_dir()?;
+
+            &cwd.join(crash_file_path)
+        };
+
+        if !crash_file.try_exists()? {
+            println!("{ERROR} The crash file [{:?}] not found", crash_file);
+            throw!(Error::CrashFileNotFound);
+        }
+
+        // cargo target directory
+        let cargo_target_dir = config.get_libfuzzer_target_dir();
+
+        let host_triple = get_host_triple().await?;
+
+        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+
+        rustflags.push_str(LIBFUZZER_RUSTFLAGS);
+        rustflags.push_str(" --cfg fuzzing_debug");
+
+        // the replay log is stored next to the crash file
+        let replay_log = format!("{}.{}", crash_file.display(), REPLAY_LOG_EXTENSION);
+
+        let mut child = Command::new("cargo")
+            .env("TRIDENT_LOG", "1")
+            .env(TRIDENT_REPLAY_LOG, replay_log)
+            .env("RUSTFLAGS", rustflags)
+            .arg("run")
+            .args(["--target", &host_triple])
+            .args(["--target-dir", &cargo_target_dir])
+            .args(["--bin", &target])
+            .arg("--")
+            .arg(crash_file)
+            .spawn()?;
+
+        // the crash is expected to abort the process, so the exit status is not checked
+        child.wait().await?;
+    }
+}
+
+/// Builds the given target with the libFuzzer instrumentation.
+#[throws]
+async fn build_libfuzzer_target(target: &str, host_triple: &str, cargo_target_dir: &str) {
+    let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+
+    rustflags.push_str(LIBFUZZER_RUSTFLAGS);
+
+    let mut child = Command::new("cargo")
+        .env("RUSTFLAGS", rustflags)
+        .arg("build")
+        .args(["--target", host_triple])
+        .args(["--target-dir", cargo_target_dir])
+        .args(["--bin", target])
+        .spawn()?;
+    Commander::handle_child(&mut child).await?;
+}
+
+/// Obtains the target triple of the host from `rustc -vV`.
+#[throws]
+pub(super) async fn get_host_triple() -> String {
+    let output = Command::new("rustc").arg("-vV").output().await?;
+    let output = String::from_utf8(output.stdout)?;
+
+    match output.lines().find_map(|line| line.strip_prefix("host: ")) {
+        Some(host_triple) => host_triple.trim().to_string(),
+        None => throw!(Error::HostTripleNotFound),
+    }
+}
@@ -1,4 +1,5 @@
 use fehler::{throw, throws};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::{io, process::Stdio, string::FromUtf8Error};
 use thiserror::Error;
@@ -9,10 +10,18 @@ use tokio::{
 };
 
 mod afl;
+mod coverage;
+mod dashboard;
 mod honggfuzz;
+mod libfuzzer;
+mod reproduce;
+mod triage;
 
+use crate::constants::*;
+use crate::idl_loader::load_idls;
+use dashboard::Dashboard;
 use tokio::io::AsyncBufReadExt;
-use trident_fuzz::fuzz_stats::FuzzingStatistics;
+use trident_fuzz::fuzz_stats::{FuzzingStatistics, StatsFormat};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -20,6 +29,8 @@ pub enum Error {
     Io(#[from] io::Error),
     #[error("{0:?}")]
     Utf8(#[from] FromUtf8Error),
+    #[error("{0:?}")]
+    Json(#[from] serde_json::Error),
     #[error("build programs failed")]
     BuildProgramsFailed,
     #[error("fuzzing failed")]
@@ -28,10 +39,31 @@ pub enum Error {
     NotInitialized,
     #[error("the crash file does not exist")]
     CrashFileNotFound,
+    #[error("the fuzz test does not define the new_client function")]
+    NewClientNotFound,
     #[error("The Solana project does not contain any programs")]
     NoProgramsFound,
     #[error("Incorrect AFL workspace provided")]
     BadAFLWorkspace,
+    #[error("the corpus directory does not exist or is empty")]
+    CorpusNotFound,
+    #[error("unable to obtain the host target triple from rustc")]
+    HostTripleNotFound,
+    #[error("LLVM tools not found, install them with `rustup component add llvm-tools-preview`")]
+    LlvmToolsNotFound,
+    #[error("no coverage data was collected")]
+    CoverageNotCollected,
+    #[error("generating the coverage report failed, make sure the LLVM tools match the LLVM version of rustc")]
+    CoverageReportFailed,
+}
+
+/// Machine-readable export of the fuzzing statistics requested on the command line,
+/// written to `output` or to the standard output if no path is provided. Only the statistics
+/// of the current session are exported unless `cumulative` is set.
+pub struct StatsExport {
+    pub format: StatsFormat,
+    pub output: Option<PathBuf>,
+    pub cumulative: bool,
 }
 
 /// `Commander` allows you to start localnet, build programs,
@@ -146,34 +178,118 @@ impl Commander {
     /// or an interrupt from the user, preventing premature termination of the logging task if scenarios where reading is faster than fuzzing,
     /// which should not be common.
     ///
+    /// The statistics are merged into the statistics of the previous sessions of the `target`,
+    /// which are checkpointed periodically so the statistics are not lost if the process is killed.
+    ///
+    /// If the `dashboard` is provided, the statistics are redrawn on the screen every second
+    /// while the fuzzing is running, instead of being shown only once it finishes.
+    /// If the `stats_export` is provided, the statistics are also exported in the requested format.
+    ///
     /// # Arguments
     /// * `child` - A mutable reference to a `Child` process, representing the child fuzzing process.
+    /// * `target` - Name of the fuzz test the statistics are collected for.
+    /// * `dashboard` - Optional live dashboard refreshed with the collected statistics.
+    /// * `stats_export` - Optional machine-readable export of the statistics.
     ///
     /// # Errors
     /// * `Error::FuzzingFailed` - Thrown if there's an issue with managing the child process, such as failing to wait on the child process.
     #[throws]
-    async fn handle_child_with_stats(child: &mut Child) {
-        let stdout = child
-            .stdout
-            .take()
-            .expect("child did not have a handle to stdout");
+    async fn handle_child_with_stats(
+        &self,
+        child: &mut Child,
+        target: &str,
+        dashboard: Option<Dashboard>,
+        stats_export: Option<&StatsExport>,
+    ) {
+        self.handle_children_with_stats(
+            std::slice::from_mut(child),
+            target,
+            dashboard,
+            stats_export,
+        )
+        .await?;
+    }
+
+    /// Manages multiple child processes running in parallel, e.g. the AFL main and secondary instances.
+    /// Waits until all of them exit or a Ctrl+C signal is received, which is delivered to the whole
+    /// process group, so the children are waited for to finish gracefully.
+    ///
+    /// # Errors
+    /// * Throws `Error::FuzzingFailed` if any of the child processes fails.
+    #[throws]
+    async fn handle_children(children: &mut [Child]) {
+        tokio::select! {
+            res = wait_children(children) =>
+                match res {
+                    Ok(success) => if !success {
+                        throw!(Error::FuzzingFailed);
+                    },
+                    Err(_) => throw!(Error::FuzzingFailed),
+            },
+            _ = signal::ctrl_c() => {
+                wait_children(children).await?;
 
-        let reader = tokio::io::BufReader::new(stdout);
+                tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+            },
+        }
+    }
 
+    /// Manages multiple child fuzzing processes, aggregating the statistics from the standard
+    /// output of all of them into a single table, see `handle_child_with_stats`.
+    ///
+    /// # Errors
+    /// * `Error::FuzzingFailed` - Thrown if any of the child processes fails.
+    #[throws]
+    async fn handle_children_with_stats(
+        &self,
+        children: &mut [Child],
+        target: &str,
+        dashboard: Option<Dashboard>,
+        stats_export: Option<&StatsExport>,
+    ) {
         let fuzz_end = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
-        let fuzz_end_clone = std::sync::Arc::clone(&fuzz_end);
 
-        let stats_handle: tokio::task::JoinHandle<Result<FuzzingStatistics, std::io::Error>> =
-            tokio::spawn(async move {
-                let mut stats_logger = FuzzingStatistics::new();
+        // the statistics of this session are added to the statistics of the previous ones
+        let stats_file = self.get_stats_file(target);
+        let mut stats = self.load_stats(&stats_file)?;
+        stats.sessions += 1;
+        let stats_logger = std::sync::Arc::new(std::sync::Mutex::new(stats));
+
+        // the statistics of this session alone are kept for the export
+        let mut session_stats = FuzzingStatistics::new();
+        session_stats.sessions = 1;
+        session_stats.set_error_names(self.get_error_names());
+        let session_stats_logger = std::sync::Arc::new(std::sync::Mutex::new(session_stats));
+
+        let mut stats_handles: Vec<tokio::task::JoinHandle<Result<(), std::io::Error>>> = vec![];
 
+        for child in children.iter_mut() {
+            let stdout = child
+                .stdout
+                .take()
+                .expect("child did not have a handle to stdout");
+
+            let reader = tokio::io::BufReader::new(stdout);
+
+            let fuzz_end_clone = std::sync::Arc::clone(&fuzz_end);
+            let stats_logger_clone = std::sync::Arc::clone(&stats_logger);
+            let session_stats_logger_clone = std::sync::Arc::clone(&session_stats_logger);
+
+            stats_handles.push(tokio::spawn(async move {
                 let mut lines = reader.lines();
                 loop {
                     let _line = lines.next_line().await;
                     match _line {
                         Ok(__line) => match __line {
                             Some(content) => {
-                                stats_logger.insert_serialized(&content);
+                                stats_logger_clone
+                                    .lock()
+                                    .expect("Statistics lock poisoned")
+                                    .insert_serialized(&content);
+                                session_stats_logger_clone
+                                    .lock()
+                                    .expect("Statistics lock poisoned")
+                                    .insert_serialized(&content);
                             }
                             None => {
                                 if fuzz_end_clone.load(std::sync::atomic::Ordering::SeqCst) {
@@ -184,41 +300,187 @@ impl Commander {
                         Err(e) => return Err(e),
                     }
                 }
-                Ok(stats_logger)
-            });
+                Ok(())
+            }));
+        }
 
-        tokio::select! {
-            res = child.wait() =>{
-                fuzz_end.store(true, std::sync::atomic::Ordering::SeqCst);
+        let fuzz_end_clone = std::sync::Arc::clone(&fuzz_end);
+        let stats_logger_clone = std::sync::Arc::clone(&stats_logger);
+        let stats_file_clone = stats_file.clone();
+        let checkpoint_handle = tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(STATS_CHECKPOINT_INTERVAL));
+            while !fuzz_end_clone.load(std::sync::atomic::Ordering::SeqCst) {
+                interval.tick().await;
+                let checkpoint = stats_logger_clone
+                    .lock()
+                    .expect("Statistics lock poisoned")
+                    .save(&stats_file_clone);
+                if let Err(e) = checkpoint {
+                    println!("{ERROR} Unable to checkpoint the statistics: {}", e);
+                }
+            }
+        });
 
-                match res {
-                    Ok(status) => {
-                        if !status.success() {
-                            throw!(Error::FuzzingFailed);
-                        }
-                    },
-                    Err(_) => throw!(Error::FuzzingFailed),
+        let dashboard_handle = dashboard.map(|mut dashboard| {
+            let fuzz_end_clone = std::sync::Arc::clone(&fuzz_end);
+            let stats_logger_clone = std::sync::Arc::clone(&stats_logger);
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(
+                    DASHBOARD_REFRESH_INTERVAL,
+                ));
+                while !fuzz_end_clone.load(std::sync::atomic::Ordering::SeqCst) {
+                    interval.tick().await;
+                    dashboard
+                        .refresh(&stats_logger_clone.lock().expect("Statistics lock poisoned"));
                 }
+            })
+        });
+
+        let success = tokio::select! {
+            res = wait_children(children) =>{
+                fuzz_end.store(true, std::sync::atomic::Ordering::SeqCst);
+
+                res.unwrap_or_default()
             },
             _ = signal::ctrl_c() => {
                 fuzz_end.store(true, std::sync::atomic::Ordering::SeqCst);
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                true
             },
+        };
+
+        checkpoint_handle.abort();
+        if let Some(dashboard_handle) = dashboard_handle {
+            dashboard_handle.abort();
         }
-        let stats_result = stats_handle
-            .await
-            .expect("Unable to obtain Statistics Handle");
-        match stats_result {
-            Ok(stats_result) => {
-                stats_result.show_table();
-            }
-            Err(e) => {
+        for stats_handle in stats_handles {
+            let stats_result = stats_handle
+                .await
+                .expect("Unable to obtain Statistics Handle");
+            if let Err(e) = stats_result {
                 println!("Statistics thread exited with the Error: {}", e);
             }
         }
+
+        let stats = stats_logger.lock().expect("Statistics lock poisoned");
+        stats.save(&stats_file)?;
+        stats.show_table();
+        println!(
+            "{FINISH} Statistics of {} session(s) saved to [{}]",
+            stats.sessions,
+            stats_file.display()
+        );
+
+        if let Some(stats_export) = stats_export {
+            let exported = if stats_export.cumulative {
+                stats.export(stats_export.format, target)
+            } else {
+                session_stats_logger
+                    .lock()
+                    .expect("Statistics lock poisoned")
+                    .export(stats_export.format, target)
+            };
+            match &stats_export.output {
+                Some(output) => {
+                    if let Some(parent) = output.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(output, exported)?;
+                    println!("{FINISH} Statistics exported to [{}]", output.display());
+                }
+                None => println!("{}", exported),
+            }
+        }
+
+        if !success {
+            throw!(Error::FuzzingFailed);
+        }
+    }
+
+    /// Path to the JSON file with the cumulative statistics of the given fuzz test.
+    fn get_stats_file(&self, target: &str) -> PathBuf {
+        self.root
+            .join(FUZZING_STATS_DIRECTORY)
+            .join(format!("{}.json", target))
+    }
+
+    /// Loads the statistics of the previous sessions, the custom program errors are named
+    /// after the `errors` section of the IDLs of the programs in the workspace.
+    /// A file which cannot be parsed is kept as `.bak` and the statistics start from empty.
+    #[throws]
+    fn load_stats(&self, stats_file: &Path) -> FuzzingStatistics {
+        let mut stats = match FuzzingStatistics::load(stats_file) {
+            Ok(stats) => stats,
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                let backup_file = stats_file.with_extension("json.bak");
+                std::fs::rename(stats_file, &backup_file)?;
+                println!(
+                    "{WARNING} The statistics in [{}] cannot be parsed ({}), moved to [{}] and starting from empty statistics",
+                    stats_file.display(),
+                    e,
+                    backup_file.display()
+                );
+                FuzzingStatistics::default()
+            }
+            Err(e) => throw!(e),
+        };
+        stats.set_error_names(self.get_error_names());
+        stats
+    }
+
+    /// Names of the custom program errors keyed by `Custom(<code>)@<program_id>`, which is
+    /// how the failed transactions are recorded in the statistics.
+    fn get_error_names(&self) -> HashMap<String, String> {
+        // the statistics are useful even without the IDLs, only the error codes are shown then
+        let idls = load_idls(self.root.join(IDL_DIRECTORY), None).unwrap_or_default();
+
+        idls.iter()
+            .filter(|idl| !idl.address.is_empty())
+            .flat_map(|idl| {
+                idl.errors.iter().map(|error| {
+                    (
+                        format!("Custom({})@{}", error.code, idl.address),
+                        format!("{} ({})", error.name, error.code),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Shows the cumulative statistics of the given fuzz test collected across all sessions.
+    #[throws]
+    pub async fn run_stats(&self, target: String) {
+        let stats_file = self.get_stats_file(&target);
+
+        if !stats_file.exists() {
+            println!(
+                "{SKIP} No statistics found for [{}], run the fuzz test with `fuzzing_with_stats = true` first",
+                target
+            );
+            return;
+        }
+
+        let stats = self.load_stats(&stats_file)?;
+        stats.show_table();
+        println!(
+            "Statistics of [{}] collected across {} session(s), stored in [{}]",
+            target,
+            stats.sessions,
+            stats_file.display()
+        );
     }
 }
 
+/// Waits for all child processes and returns whether all of them exited successfully.
+async fn wait_children(children: &mut [Child]) -> io::Result<bool> {
+    let mut success = true;
+    for child in children.iter_mut() {
+        success &= child.wait().await?.success();
+    }
+    Ok(success)
+}
+
 fn get_crash_dir_and_ext(
     root: &Path,
     target: &str,
@@ -288,6 +550,66 @@ fn get_cmd_option_value<'a>(
     value
 }
 
+/// Returns the directories of the AFL instances within the AFL output directory, i.e.
+/// `default` for a single instance or `main` and `secondary_<N>` for multiple instances.
+fn get_afl_instance_dirs(afl_workspace_out: &Path) -> Vec<PathBuf> {
+    let mut instance_dirs: Vec<PathBuf> = std::fs::read_dir(afl_workspace_out)
+        .map(|entries| {
+            entries
+                .filter_map(|res| res.ok())
+                .map(|dir_entry| dir_entry.path())
+                .filter(|path| path.join(AFL_QUEUE_DIRECTORY).is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    instance_dirs.sort();
+    instance_dirs
+}
+
+/// Returns the crash files stored by AFL in the `crashes` directory of an instance.
+fn get_afl_crash_files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|res| res.ok())
+                .map(|dir_entry| dir_entry.path())
+                // AFL stores README.txt next to the crashes
+                .filter(|path| {
+                    path.file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with("id:"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn get_libfuzzer_crash_files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|res| res.ok())
+                .map(|dir_entry| dir_entry.path())
+                // the timeouts and the out-of-memory inputs are stored next to the crashes
+                .filter(|path| {
+                    path.file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with("crash-"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn get_corpus_size(dir: &Path) -> usize {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|res| res.ok())
+                .filter(|dir_entry| dir_entry.path().is_file())
+                .count()
+        })
+        .unwrap_or_default()
+}
+
 fn get_crash_files(
     dir: &PathBuf,
     extension: &str,
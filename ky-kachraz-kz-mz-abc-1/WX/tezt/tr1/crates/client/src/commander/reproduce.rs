@@ -0,0 +1,219 @@
+use fehler::{throw, throws};
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use trident_config::TridentConfig;
+use trident_fuzz::crash_report::{CrashReport, TRIDENT_CRASH_REPORT};
+use trident_fuzz::replay_log::{ReplayLog, TRIDENT_REPLAY_LOG};
+
+use crate::constants::*;
+
+use super::{Commander, Error};
+
+impl Commander {
+    /// Replays the crash file on the given target and optionally emits a regression test
+    /// which executes the same transactions without the fuzzer runtime.
+    #[throws]
+    pub async fn run_reproduce(&self, target: String, crash_file_path: String, emit_test: bool) {
+        let config = TridentConfig::new();
+
+        let crash_file = Path::new(&crash_file_path);
+
+        let crash_file = if crash_file.is_absolute() {
+            crash_file.to_path_buf()
+        } else {
+            std::env::current_dir()?.join(crash_file)
+        };
+
+        if !crash_file.try_exists()? {
+            println!("{ERROR} The crash file [{:?}] not found", crash_file);
+            throw!(Error::CrashFileNotFound);
+        }
+
+        let fuzz_test_dir = self.root.join(TESTS_WORKSPACE_DIRECTORY).join(&target);
+        let fuzz_test_file = fuzz_test_dir.join(FUZZ_TEST);
+        if emit_test && !fuzz_test_file.exists() {
+            println!(
+                "{ERROR} The fuzz test [{}] not found",
+                fuzz_test_file.display()
+            );
+            throw!(Error::NotInitialized);
+        }
+        // the regression test has to be executed with the same client as the fuzz test
+        if emit_test && !std::fs::read_to_string(&fuzz_test_file)?.contains("fn new_client()") {
+            println!(
+                "{ERROR} The fuzz test [{}] does not define `fn new_client() -> TridentSVM`, move the creation of the client from `main` into it",
+                fuzz_test_file.display()
+            );
+            throw!(Error::NewClientNotFound);
+        }
+
+        let cargo_target_dir =
+            std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| config.get_honggfuzz_target_dir());
+
+        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+
+        rustflags.push_str("--cfg honggfuzz_debug --cfg fuzzing_debug");
+
+        let replay_log = format!("{}.{}", crash_file.display(), REPLAY_LOG_EXTENSION);
+        let crash_report =
+            std::env::temp_dir().join(format!("trident-crash-report-{}.json", std::process::id()));
+        if crash_report.exists() {
+            std::fs::remove_file(&crash_report)?;
+        }
+
+        let mut child = Command::new("cargo")
+            .env("CARGO_TARGET_DIR", cargo_target_dir)
+            .env("RUSTFLAGS", rustflags)
+            .env(TRIDENT_REPLAY_LOG, &replay_log)
+            .env(TRIDENT_CRASH_REPORT, &crash_report)
+            .arg("run")
+            .arg("--bin")
+            .arg(&target)
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(crash_file.to_string_lossy().as_bytes())
+                .await?;
+        }
+
+        // the crash is expected to make the fuzz test fail, so the exit status is not checked
+        child.wait().await?;
+
+        match std::fs::read_to_string(&crash_report)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CrashReport>(&content).ok())
+        {
+            Some(report) => {
+                std::fs::remove_file(&crash_report)?;
+                println!(
+                    "{FINISH} Crash reproduced: '{}' at {} in {}",
+                    report.message,
+                    report.location.as_deref().unwrap_or("-"),
+                    report.transaction.as_deref().unwrap_or("-")
+                );
+            }
+            None => println!("{SKIP} The crash did not reproduce"),
+        }
+
+        if !emit_test {
+            return;
+        }
+
+        let replay_log: ReplayLog = match std::fs::read_to_string(&replay_log) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(_) => {
+                println!("{ERROR} The replay log [{}] not found", replay_log);
+                throw!(Error::FuzzingFailed);
+            }
+        };
+
+        let test_name = regression_test_name(&crash_file);
+        let regression_test = regression_test_code(&test_name, &crash_file, &replay_log);
+        let regression_test = Commander::format_program_code(&regression_test).await?;
+
+        let regressions_dir = fuzz_test_dir.join(REGRESSIONS_DIRECTORY);
+        std::fs::create_dir_all(&regressions_dir)?;
+
+        let regression_test_file = regressions_dir.join(format!("{}.rs", test_name));
+        std::fs::write(&regression_test_file, regression_test)?;
+
+        // register the test within the regressions module and the fuzz test binary
+        append_if_missing(
+            &regressions_dir.join("mod.rs"),
+            &format!("mod {};", test_name),
+        )?;
+        append_if_missing(
+            &fuzz_test_file,
+            &format!("#[cfg(test)]\nmod {};", REGRESSIONS_DIRECTORY),
+        )?;
+
+        println!(
+            "{FINISH} Regression test saved to [{}], run it with `cargo test --bin {}`",
+            regression_test_file.display(),
+            target
+        );
+    }
+}
+
+/// Name of the test function derived from the crash file name.
+fn regression_test_name(crash_file: &Path) -> String {
+    let file_name = crash_file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut name: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .take(REGRESSION_TEST_NAME_LENGTH)
+        .collect();
+    name.insert_str(0, "crash_");
+    name
+}
+
+fn regression_test_code(test_name: &str, crash_file: &Path, replay_log: &ReplayLog) -> String {
+    let mut transactions = String::new();
+    for flow in &replay_log.flows {
+        transactions.push_str(&format!("// {}\n", flow.name));
+        for transaction in &flow.transactions {
+            let execute = if transaction.hooks {
+                "execute"
+            } else {
+                "execute_no_hooks"
+            };
+            transactions.push_str(&format!(
+                "let mut transaction = {};\n\
+                transaction.set_instruction_accounts(&mut fuzz_test.client, fuzz_accounts);\n\
+                let _ = transaction.{}(&mut fuzz_test.client);\n",
+                transaction.rust_code, execute
+            ));
+        }
+    }
+
+    format!(
+        "// Regression test generated by `trident fuzz reproduce` from the crash file\n\
+        // {}\n\
+        use trident_fuzz::fuzzing::*;\n\
+        \n\
+        use crate::instructions::*;\n\
+        use crate::transactions::*;\n\
+        use crate::new_client;\n\
+        use crate::FuzzTest;\n\
+        \n\
+        #[test]\n\
+        fn {}() {{\n\
+            FuzzTest::new(new_client()).reproduce(|fuzz_test, fuzz_accounts| {{\n\
+                {}\n\
+            }});\n\
+        }}\n",
+        crash_file.display(),
+        test_name,
+        transactions
+    )
+}
+
+fn append_if_missing(file: &Path, line: &str) -> std::io::Result<()> {
+    let content = std::fs::read_to_string(file).unwrap_or_default();
+    if !content.contains(line) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file)?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(file)?;
+        }
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
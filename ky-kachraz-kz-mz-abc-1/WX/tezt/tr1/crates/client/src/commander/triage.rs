@@ -0,0 +1,226 @@
+use fehler::throws;
+use prettytable::{row, Table};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use trident_config::TridentConfig;
+use trident_fuzz::crash_report::{CrashReport, TRIDENT_CRASH_REPORT};
+
+use crate::constants::*;
+
+use super::{
+    get_afl_crash_files, get_afl_instance_dirs, get_crash_dir_and_ext, get_crash_files, Commander,
+    Error,
+};
+
+/// Crashes sharing the same root cause.
+#[derive(Debug, serde::Serialize)]
+struct CrashGroup {
+    transaction: Option<String>,
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+    crash_files: Vec<PathBuf>,
+}
+
+/// Result of the triage stored as JSON next to the crash files.
+#[derive(Debug, serde::Serialize)]
+struct TriageReport {
+    target: String,
+    crashes: usize,
+    groups: Vec<CrashGroup>,
+    not_reproduced: Vec<PathBuf>,
+}
+
+impl Commander {
+    /// Replays all crash files of the given target in debug mode and groups
+    /// them by the root cause of the crash.
+    #[throws]
+    pub async fn run_triage(&self, target: String, afl: bool) {
+        let config = TridentConfig::new();
+
+        let (crash_dir, mut crash_files) = if afl {
+            // crashes of all instances are triaged together
+            let crash_dir = PathBuf::from(config.get_afl_workspace_out());
+            let crash_files = get_afl_instance_dirs(&crash_dir)
+                .iter()
+                .flat_map(|instance_dir| {
+                    get_afl_crash_files(&instance_dir.join(AFL_CRASHES_DIRECTORY))
+                })
+                .collect();
+            (crash_dir, crash_files)
+        } else {
+            let hfuzz_run_args = std::env::var("HFUZZ_RUN_ARGS").unwrap_or_default();
+            let fuzz_args = config.get_honggfuzz_args(hfuzz_run_args);
+            let hfuzz_workspace = std::env::var("HFUZZ_WORKSPACE")
+                .unwrap_or_else(|_| config.get_honggfuzz_workspace());
+
+            let (crash_dir, ext) =
+                get_crash_dir_and_ext(&self.root, &target, &fuzz_args, &hfuzz_workspace);
+            let crash_files = get_crash_files(&crash_dir, &ext).unwrap_or_default();
+            (crash_dir, crash_files)
+        };
+
+        if crash_files.is_empty() {
+            println!("{SKIP} No crash files found in [{}]", crash_dir.display());
+            return;
+        }
+        crash_files.sort();
+
+        // all crash files are replayed with the same debug build, the flags are the same
+        // as for debug-hfuzz so the build can be shared
+        let cargo_target_dir =
+            std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| config.get_honggfuzz_target_dir());
+
+        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+
+        rustflags.push_str("--cfg honggfuzz_debug --cfg fuzzing_debug");
+
+        let mut child = Command::new("cargo")
+            .env("CARGO_TARGET_DIR", &cargo_target_dir)
+            .env("RUSTFLAGS", rustflags)
+            .arg("build")
+            .arg("--bin")
+            .arg(&target)
+            .spawn()?;
+        Self::handle_child(&mut child).await?;
+
+        let binary = Path::new(&cargo_target_dir).join("debug").join(&target);
+        let report_file =
+            std::env::temp_dir().join(format!("trident-crash-report-{}.json", std::process::id()));
+
+        let mut groups: BTreeMap<String, CrashGroup> = BTreeMap::new();
+        let mut not_reproduced = vec![];
+
+        for (index, crash_file) in crash_files.iter().enumerate() {
+            println!(
+                "[{}/{}] Replaying [{}]",
+                index + 1,
+                crash_files.len(),
+                crash_file.display()
+            );
+            match replay_crash(&binary, crash_file, &report_file).await? {
+                Some(report) => {
+                    groups
+                        .entry(root_cause(&report))
+                        .or_insert_with(|| CrashGroup {
+                            transaction: report.transaction,
+                            message: report.message,
+                            location: report.location,
+                            backtrace: report.backtrace,
+                            crash_files: vec![],
+                        })
+                        .crash_files
+                        .push(crash_file.clone());
+                }
+                None => not_reproduced.push(crash_file.clone()),
+            }
+        }
+
+        let mut groups: Vec<CrashGroup> = groups.into_values().collect();
+        groups.sort_by(|a, b| b.crash_files.len().cmp(&a.crash_files.len()));
+
+        let report = TriageReport {
+            target,
+            crashes: crash_files.len(),
+            groups,
+            not_reproduced,
+        };
+
+        show_triage_table(&report);
+
+        let report_path = crash_dir.join(TRIAGE_REPORT_FILE);
+        std::fs::write(&report_path, serde_json::to_string_pretty(&report)?)?;
+
+        println!(
+            "{FINISH} Triage report saved to [{}]",
+            report_path.display()
+        );
+    }
+}
+
+/// Replays a single crash file and returns the report of the first panic, if any.
+#[throws]
+async fn replay_crash(binary: &Path, crash_file: &Path, report_file: &Path) -> Option<CrashReport> {
+    if report_file.exists() {
+        std::fs::remove_file(report_file)?;
+    }
+
+    let mut child = Command::new(binary)
+        .env(TRIDENT_CRASH_REPORT, report_file)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(crash_file.to_string_lossy().as_bytes())
+            .await?;
+    }
+
+    // the child is killed on drop if the replay hangs
+    let _ = tokio::time::timeout(
+        tokio::time::Duration::from_secs(TRIAGE_REPLAY_TIMEOUT),
+        child.wait(),
+    )
+    .await;
+
+    std::fs::read_to_string(report_file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Crashes are considered to have the same root cause if they panic in the same
+/// transaction at the same location with the same message, ignoring numbers
+/// which usually differ between the inputs.
+fn root_cause(report: &CrashReport) -> String {
+    let message: String = report
+        .message
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split(|c: char| c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("N");
+
+    format!(
+        "{}|{}|{}",
+        report.transaction.as_deref().unwrap_or_default(),
+        report.location.as_deref().unwrap_or_default(),
+        message
+    )
+}
+
+fn show_triage_table(report: &TriageReport) {
+    let mut table = Table::new();
+    table.add_row(row![
+        "Root Cause",
+        "Transaction",
+        "Panic Message",
+        "Location",
+        "Crashes"
+    ]);
+    for (index, group) in report.groups.iter().enumerate() {
+        table.add_row(row![
+            index + 1,
+            group.transaction.as_deref().unwrap_or("-"),
+            group.message.lines().next().unwrap_or_default(),
+            group.location.as_deref().unwrap_or("-"),
+            group.crash_files.len()
+        ]);
+    }
+    table.printstd();
+
+    if !report.not_reproduced.is_empty() {
+        println!(
+            "{ERROR} {} out of {} crash files did not reproduce",
+            report.not_reproduced.len(),
+            report.crashes
+        );
+    }
+}
@@ -17,9 +17,11 @@ pub mod ___private {
     pub use super::cleaner::*;
     pub use super::commander::Commander;
     pub use super::commander::Error;
+    pub use super::commander::StatsExport;
     pub use super::idl_loader::*;
     // pub use super::source_code_generators::*;
     pub use super::test_generator::TestGenerator;
+    pub use trident_fuzz::fuzz_stats::StatsFormat;
 }
 
 mod constants {
@@ -28,6 +30,9 @@ mod constants {
     pub(crate) const TRIDENT_TOML: &str = "Trident.toml";
     pub(crate) const ANCHOR_TOML: &str = "Anchor.toml";
 
+    // idl
+    pub(crate) const IDL_DIRECTORY: &str = "target/idl";
+
     // tests
     pub(crate) const TESTS_WORKSPACE_DIRECTORY: &str = "trident-tests";
     pub(crate) const INSTRUCTIONS_DIRECTORY: &str = "instructions";
@@ -38,13 +43,57 @@ mod constants {
     pub(crate) const FUZZ_TRANSACTIONS_FILE_NAME: &str = "fuzz_transactions.rs";
     pub(crate) const TYPES_FILE_NAME: &str = "types.rs";
     pub(crate) const FUZZ_TEST: &str = "test_fuzz.rs";
+    pub(crate) const REGRESSIONS_DIRECTORY: &str = "regressions";
+    pub(crate) const REGRESSION_TEST_NAME_LENGTH: usize = 64;
 
     // honggfuzz
     pub(crate) const CARGO_TARGET_DIR_DEFAULT_HFUZZ: &str =
         "trident-tests/fuzzing/honggfuzz/hfuzz_target";
+    pub(crate) const HFUZZ_INPUT_DIRECTORY: &str = "input";
+    pub(crate) const REPLAY_LOG_EXTENSION: &str = "replay.json";
 
     // afl
     pub(crate) const CARGO_TARGET_DIR_DEFAULT_AFL: &str = "trident-tests/fuzzing/afl/afl_target";
+    pub(crate) const AFL_MAIN_INSTANCE: &str = "main";
+    pub(crate) const AFL_SECONDARY_INSTANCE: &str = "secondary";
+    pub(crate) const AFL_QUEUE_DIRECTORY: &str = "queue";
+    pub(crate) const AFL_CRASHES_DIRECTORY: &str = "crashes";
+    pub(crate) const AFL_MINIMIZED_DIRECTORY: &str = "minimized";
+    pub(crate) const AFL_MERGED_QUEUE_DIRECTORY: &str = "merged_queue";
+
+    // libfuzzer
+    pub(crate) const CARGO_TARGET_DIR_DEFAULT_LIBFUZZER: &str =
+        "trident-tests/fuzzing/libfuzzer/libfuzzer_target";
+    // SanitizerCoverage instrumentation used by libFuzzer to guide the fuzzing
+    pub(crate) const LIBFUZZER_RUSTFLAGS: &str = " --cfg libfuzzer \
+        -Cpasses=sancov-module \
+        -Cllvm-args=-sanitizer-coverage-level=4 \
+        -Cllvm-args=-sanitizer-coverage-inline-8bit-counters \
+        -Cllvm-args=-sanitizer-coverage-pc-table \
+        -Cllvm-args=-sanitizer-coverage-trace-compares";
+    pub(crate) const LIBFUZZER_MINIMIZED_DIRECTORY: &str = "minimized";
+
+    // coverage
+    pub(crate) const CARGO_TARGET_DIR_DEFAULT_COVERAGE: &str =
+        "trident-tests/fuzzing/coverage/coverage_target";
+    pub(crate) const COVERAGE_DIRECTORY: &str = "trident-tests/fuzzing/coverage";
+    pub(crate) const COVERAGE_PROFRAW_DIRECTORY: &str = "profraw";
+    pub(crate) const COVERAGE_PROFDATA_FILE: &str = "coverage.profdata";
+    pub(crate) const COVERAGE_HTML_DIRECTORY: &str = "html";
+    pub(crate) const COVERAGE_LCOV_FILE: &str = "lcov.info";
+    // source-based coverage of the natively linked programs
+    pub(crate) const COVERAGE_RUSTFLAGS: &str = " -Cinstrument-coverage --cfg fuzzing_coverage";
+
+    // statistics
+    pub(crate) const FUZZING_STATS_DIRECTORY: &str = "trident-tests/fuzzing/stats";
+    pub(crate) const STATS_CHECKPOINT_INTERVAL: u64 = 10;
+    // milliseconds
+    pub(crate) const DASHBOARD_REFRESH_INTERVAL: u64 = 1000;
+    pub(crate) const HONGGFUZZ_LOGFILE: &str = "HONGGFUZZ.log";
+
+    // triage
+    pub(crate) const TRIAGE_REPORT_FILE: &str = "triage.json";
+    pub(crate) const TRIAGE_REPLAY_TIMEOUT: u64 = 60;
 
     // workspace
     pub(crate) const GIT_IGNORE: &str = ".gitignore";
@@ -53,4 +102,5 @@ mod constants {
     pub(crate) const SKIP: &str = "\x1b[33mSkip\x1b[0m";
     pub(crate) const FINISH: &str = "\x1b[92mFinished\x1b[0m";
     pub(crate) const ERROR: &str = "\x1b[31mError\x1b[0m";
+    pub(crate) const WARNING: &str = "\x1b[33mWarning\x1b[0m";
 }
@@ -63,6 +63,8 @@ impl TestGenerator {
 
         self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_HFUZZ)?;
         self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_AFL)?;
+        self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_LIBFUZZER)?;
+        self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_COVERAGE)?;
     }
 
     #[throws]
@@ -76,6 +78,8 @@ impl TestGenerator {
 
         self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_HFUZZ)?;
         self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_AFL)?;
+        self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_LIBFUZZER)?;
+        self.update_gitignore(CARGO_TARGET_DIR_DEFAULT_COVERAGE)?;
 
         // update_package_metadata(&self.program_packages, &self.versions_config).await?;
     }
@@ -44,7 +44,10 @@ impl<C: FuzzClient + std::panic::RefUnwindSafe> FuzzTest<C> {
         ));
     }
 }
+/// Creates the client for the fuzz test and the regression tests
+fn new_client() -> TridentSVM {
+    TridentSVM::new_client(&[], &TridentConfig::new())
+}
 fn main() {
-    let client = TridentSVM::new_client(&[], &TridentConfig::new());
-    FuzzTest::new(client).fuzz();
+    FuzzTest::new(new_client()).fuzz();
 }
@@ -5,32 +5,40 @@ use trident_fuzz::fuzzing::*;
 ///
 /// You can define your own custom types here.
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct Deposited {
+    pub depositor: TridentPubkey,
+    pub amount: u64,
+}
+impl Discriminator for Deposited {
+    const DISCRIMINATOR: &'static [u8] = &[111, 141, 26, 45, 161, 35, 100, 57];
+}
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct ClassicStruct {
-    field1: u8,
-    field2: u16,
-    field3: TridentPubkey,
+    pub field1: u8,
+    pub field2: u16,
+    pub field3: TridentPubkey,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct ClassicStructAccount {
-    field1: u8,
-    field2: u16,
-    field3: TridentPubkey,
+    pub field1: u8,
+    pub field2: u16,
+    pub field3: TridentPubkey,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct DataAccount {
-    unit_struct: UnitStruct,
-    tuple_struct: TupleStruct,
-    classic_struct: ClassicStruct,
-    generic_struct: GenericStruct,
-    optional_fields: OptionalFields,
-    default_struct: DefaultStruct,
-    nested_struct: NestedStruct,
-    simple_enum: SimpleEnum,
-    data_enum: DataEnum,
-    multi_data_enum: MultiDataEnum,
-    named_fields_enum: NamedFieldsEnum,
-    generic_enum: GenericEnum,
-    unit_variants: UnitVariants,
+    pub unit_struct: UnitStruct,
+    pub tuple_struct: TupleStruct,
+    pub classic_struct: ClassicStruct,
+    pub generic_struct: GenericStruct,
+    pub optional_fields: OptionalFields,
+    pub default_struct: DefaultStruct,
+    pub nested_struct: NestedStruct,
+    pub simple_enum: SimpleEnum,
+    pub data_enum: DataEnum,
+    pub multi_data_enum: MultiDataEnum,
+    pub named_fields_enum: NamedFieldsEnum,
+    pub generic_enum: GenericEnum,
+    pub unit_variants: UnitVariants,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub enum DataEnum {
@@ -41,9 +49,9 @@ pub enum DataEnum {
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct DefaultStruct {
-    field1: u8,
-    field2: u16,
-    field3: TridentPubkey,
+    pub field1: u8,
+    pub field2: u16,
+    pub field3: TridentPubkey,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub enum GenericEnum {
@@ -52,8 +60,8 @@ pub enum GenericEnum {
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct GenericStruct {
-    value: T,
-    key: TridentPubkey,
+    pub value: T,
+    pub key: TridentPubkey,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub enum MultiDataEnum {
@@ -77,24 +85,24 @@ pub enum NamedFieldsEnum {
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct NestedStruct {
-    inner: ClassicStruct,
-    key: TridentPubkey,
+    pub inner: ClassicStruct,
+    pub key: TridentPubkey,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct NestedStructAccount {
-    inner: ClassicStructAccount,
+    pub inner: ClassicStructAccount,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct OptionalFields {
-    field1: Option<u8>,
-    field2: Option<u16>,
-    field3: Option<TridentPubkey>,
+    pub field1: Option<u8>,
+    pub field2: Option<u16>,
+    pub field3: Option<TridentPubkey>,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub struct OptionalFieldsAccount {
-    field1: Option<u8>,
-    field2: Option<u16>,
-    field3: Option<TridentPubkey>,
+    pub field1: Option<u8>,
+    pub field2: Option<u16>,
+    pub field3: Option<TridentPubkey>,
 }
 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
 pub enum SimpleEnum {
@@ -116,3 +124,21 @@ pub enum UnitVariants {
     VariantB,
     VariantC,
 }
+impl Discriminator for ClassicStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[10, 204, 168, 207, 9, 6, 242, 89];
+}
+impl Discriminator for DataAccount {
+    const DISCRIMINATOR: &'static [u8] = &[85, 240, 182, 158, 76, 7, 18, 233];
+}
+impl Discriminator for NestedStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[83, 54, 74, 216, 227, 166, 36, 8];
+}
+impl Discriminator for OptionalFieldsAccount {
+    const DISCRIMINATOR: &'static [u8] = &[106, 159, 212, 74, 108, 186, 212, 251];
+}
+impl Discriminator for TupleStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[178, 81, 243, 166, 161, 145, 202, 99];
+}
+impl Discriminator for UnitStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[24, 221, 246, 80, 62, 247, 138, 203];
+}
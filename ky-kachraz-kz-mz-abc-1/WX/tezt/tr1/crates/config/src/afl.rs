@@ -21,6 +21,9 @@ pub struct Afl {
     // seeds
     // -s
     pub seeds: Option<Vec<AflSeed>>,
+    // number of parallel instances
+    // -M for the first instance, -S for the others
+    pub instances: Option<u16>,
 }
 
 impl Afl {
@@ -70,6 +73,12 @@ impl Afl {
             vec![AflSeed::default()]
         }
     }
+    pub fn get_instances(&self) -> u16 {
+        // instances
+        self.instances
+            .filter(|instances| *instances > 0)
+            .unwrap_or(1)
+    }
     pub fn get_collect_build_args(&self) -> Vec<String> {
         #[allow(unused_mut)]
         let mut result = vec![];
@@ -121,6 +130,7 @@ mod tests {
                 iterations: None,
                 run_time: None,
                 seeds: None,
+                instances: None,
             }
         }
     }
@@ -176,4 +186,17 @@ mod tests {
         let arg = afl.get_collect_fuzz_args();
         assert_eq!(arg, vec!["-V", "15"]);
     }
+    #[test]
+    fn test_instances() {
+        let mut afl = Afl::clean();
+
+        // single instance by default
+        assert_eq!(afl.get_instances(), 1);
+
+        afl.instances = Some(0);
+        assert_eq!(afl.get_instances(), 1);
+
+        afl.instances = Some(4);
+        assert_eq!(afl.get_instances(), 4);
+    }
 }
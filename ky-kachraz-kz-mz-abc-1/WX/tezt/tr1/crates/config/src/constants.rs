@@ -15,5 +15,15 @@ pub const AFL_WORKSPACE_DEFAULT_IN: &str = "trident-tests/fuzzing/afl/afl_worksp
 
 pub const AFL_WORKSPACE_DEFAULT_OUT: &str = "trident-tests/fuzzing/afl/afl_workspace/out";
 
+// libfuzzer
+pub const CARGO_TARGET_DIR_DEFAULT_LIBFUZZER: &str =
+    "trident-tests/fuzzing/libfuzzer/libfuzzer_target";
+
+pub const LIBFUZZER_WORKSPACE_DEFAULT_CORPUS: &str =
+    "trident-tests/fuzzing/libfuzzer/libfuzzer_workspace/corpus";
+
+pub const LIBFUZZER_WORKSPACE_DEFAULT_ARTIFACTS: &str =
+    "trident-tests/fuzzing/libfuzzer/libfuzzer_workspace/artifacts";
+
 pub const DEFAULT_SEED_FILENAME: &str = "trident-seed";
 pub const DEFAULT_SEED: &str = "trident";
@@ -5,12 +5,20 @@ use solana_sdk::{
     account::{AccountSharedData, WritableAccount},
     pubkey::Pubkey,
 };
-use std::{fs, str::FromStr};
+use std::{collections::HashMap, fs, str::FromStr};
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct Fuzz {
     pub fuzzing_with_stats: Option<bool>,
+    pub live_stats: Option<bool>,
     pub allow_duplicate_txs: Option<bool>,
+    pub min_transactions: Option<usize>,
+    pub max_transactions: Option<usize>,
+    pub address_seed: Option<String>,
+    pub authorization_attacks: Option<bool>,
+    pub builtin_invariants: Option<Vec<String>>,
+    pub compute_unit_threshold: Option<u64>,
+    pub transaction_weights: Option<HashMap<String, u32>>,
     pub programs: Option<Vec<_FuzzProgram>>,
     pub accounts: Option<Vec<_FuzzAccount>>,
 }
@@ -19,8 +27,32 @@ impl Fuzz {
     pub fn get_fuzzing_with_stats(&self) -> bool {
         self.fuzzing_with_stats.unwrap_or(false)
     }
+    pub fn get_live_stats(&self) -> bool {
+        self.live_stats.unwrap_or(false)
+    }
     pub fn get_allow_duplicate_txs(&self) -> bool {
-        self.allow_duplicate_txs.unwrap_or(false)
+        self.allow_duplicate_txs.unwrap_or(true)
+    }
+    pub fn get_min_transactions(&self) -> Option<usize> {
+        self.min_transactions
+    }
+    pub fn get_max_transactions(&self) -> Option<usize> {
+        self.max_transactions
+    }
+    pub fn get_address_seed(&self) -> String {
+        self.address_seed.clone().unwrap_or_default()
+    }
+    pub fn get_authorization_attacks(&self) -> bool {
+        self.authorization_attacks.unwrap_or(false)
+    }
+    pub fn get_builtin_invariants(&self) -> Vec<String> {
+        self.builtin_invariants.clone().unwrap_or_default()
+    }
+    pub fn get_compute_unit_threshold(&self) -> Option<u64> {
+        self.compute_unit_threshold
+    }
+    pub fn get_transaction_weights(&self) -> HashMap<String, u32> {
+        self.transaction_weights.clone().unwrap_or_default()
     }
 }
 
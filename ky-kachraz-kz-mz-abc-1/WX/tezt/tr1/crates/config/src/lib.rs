@@ -3,16 +3,18 @@ pub mod argument;
 pub mod constants;
 pub mod fuzz;
 pub mod honggfuzz;
+pub mod libfuzzer;
 
 use afl::*;
 use constants::*;
 use fuzz::*;
 use honggfuzz::*;
+use libfuzzer::*;
 
 pub mod utils;
 
 use serde::Deserialize;
-use std::{fs, io};
+use std::{collections::HashMap, fs, io};
 use thiserror::Error;
 use utils::{discover_root, resolve_path};
 
@@ -32,6 +34,7 @@ pub enum Error {
 pub struct TridentConfig {
     pub honggfuzz: Option<HonggFuzz>,
     pub afl: Option<Afl>,
+    pub libfuzzer: Option<LibFuzzer>,
     pub fuzz: Option<Fuzz>,
 }
 
@@ -127,6 +130,12 @@ impl TridentConfig {
             .map(|afl| afl.get_collect_fuzz_args())
             .unwrap_or_default()
     }
+    pub fn get_afl_instances(&self) -> u16 {
+        self.afl
+            .as_ref()
+            .map(|afl| afl.get_instances())
+            .unwrap_or(1)
+    }
 
     pub fn get_initial_seed(&self) -> Vec<AflSeed> {
         self.afl
@@ -135,6 +144,49 @@ impl TridentConfig {
             .unwrap_or_else(|| vec![AflSeed::default()])
     }
     // -*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*
+    // libfuzzer
+    pub fn get_libfuzzer_target_dir(&self) -> String {
+        let path = self
+            .libfuzzer
+            .as_ref()
+            .map(|libfuzzer| libfuzzer.get_cargo_target_dir().value.unwrap())
+            .unwrap_or_else(|| CARGO_TARGET_DIR_DEFAULT_LIBFUZZER.to_string());
+        let full_path = resolve_path(&path);
+        full_path.to_str().unwrap().to_string()
+    }
+    pub fn get_libfuzzer_target_path(&self, target: &str, host_triple: &str) -> String {
+        let mut libfuzzer_target_dir = self.get_libfuzzer_target_dir();
+        libfuzzer_target_dir.push('/');
+        libfuzzer_target_dir.push_str(host_triple);
+        libfuzzer_target_dir.push_str("/debug/");
+        libfuzzer_target_dir.push_str(target);
+        libfuzzer_target_dir
+    }
+    pub fn get_libfuzzer_workspace_corpus(&self) -> String {
+        let path = self
+            .libfuzzer
+            .as_ref()
+            .map(|libfuzzer| libfuzzer.get_workspace_corpus().value.unwrap())
+            .unwrap_or_else(|| LIBFUZZER_WORKSPACE_DEFAULT_CORPUS.to_string());
+        let full_path = resolve_path(&path);
+        full_path.to_str().unwrap().to_string()
+    }
+    pub fn get_libfuzzer_workspace_artifacts(&self) -> String {
+        let path = self
+            .libfuzzer
+            .as_ref()
+            .map(|libfuzzer| libfuzzer.get_workspace_artifacts().value.unwrap())
+            .unwrap_or_else(|| LIBFUZZER_WORKSPACE_DEFAULT_ARTIFACTS.to_string());
+        let full_path = resolve_path(&path);
+        full_path.to_str().unwrap().to_string()
+    }
+    pub fn get_libfuzzer_fuzz_args(&self) -> Vec<String> {
+        self.libfuzzer
+            .as_ref()
+            .map(|libfuzzer| libfuzzer.get_collect_fuzz_args())
+            .unwrap_or_default()
+    }
+    // -*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*-*
     // fuzz
     pub fn get_fuzzing_with_stats(&self) -> bool {
         self.fuzz
@@ -142,11 +194,56 @@ impl TridentConfig {
             .map(|fuzz| fuzz.get_fuzzing_with_stats())
             .unwrap_or_default()
     }
+    pub fn get_live_stats(&self) -> bool {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_live_stats())
+            .unwrap_or_default()
+    }
+    pub fn get_address_seed(&self) -> String {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_address_seed())
+            .unwrap_or_default()
+    }
+    pub fn get_authorization_attacks(&self) -> bool {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_authorization_attacks())
+            .unwrap_or_default()
+    }
+    pub fn get_builtin_invariants(&self) -> Vec<String> {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_builtin_invariants())
+            .unwrap_or_default()
+    }
+    pub fn get_transaction_weights(&self) -> HashMap<String, u32> {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_transaction_weights())
+            .unwrap_or_default()
+    }
     pub fn get_allow_duplicate_txs(&self) -> bool {
         self.fuzz
             .as_ref()
             .map(|fuzz| fuzz.get_allow_duplicate_txs())
-            .unwrap_or_default()
+            .unwrap_or(true)
+    }
+    pub fn get_min_transactions(&self) -> Option<usize> {
+        self.fuzz
+            .as_ref()
+            .and_then(|fuzz| fuzz.get_min_transactions())
+    }
+    pub fn get_max_transactions(&self) -> Option<usize> {
+        self.fuzz
+            .as_ref()
+            .and_then(|fuzz| fuzz.get_max_transactions())
+    }
+    pub fn get_compute_unit_threshold(&self) -> Option<u64> {
+        self.fuzz
+            .as_ref()
+            .and_then(|fuzz| fuzz.get_compute_unit_threshold())
     }
 
     pub fn programs(&self) -> Vec<FuzzProgram> {
@@ -0,0 +1,291 @@
+use crate::{
+    argument::Argument,
+    constants::*,
+    utils::{arg_to_flag, resolve_path},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LibFuzzer {
+    // cargo_target_dir
+    // --target-dir
+    pub cargo_target_dir: Option<String>,
+    // libfuzzer_workspace_corpus
+    // positional corpus directory
+    pub libfuzzer_workspace_corpus: Option<String>,
+    // libfuzzer_workspace_artifacts
+    // -artifact_prefix
+    pub libfuzzer_workspace_artifacts: Option<String>,
+    // runs
+    // -runs
+    pub iterations: Option<u64>,
+    // seconds
+    // -max_total_time
+    pub run_time: Option<u64>,
+    // seconds
+    // -timeout
+    pub timeout: Option<u64>,
+    // bytes
+    // -max_len
+    pub max_len: Option<u64>,
+    // number of fuzzing jobs
+    // -jobs
+    pub jobs: Option<u16>,
+    // number of simultaneous worker processes
+    // -workers
+    pub workers: Option<u16>,
+    // number of fuzzing processes in the fork mode
+    // -fork
+    pub fork: Option<u16>,
+    // dictionary file
+    // -dict
+    pub dict: Option<String>,
+}
+
+impl LibFuzzer {
+    pub fn get_cargo_target_dir(&self) -> Argument {
+        // cargo_target_dir
+        if let Some(cargo_target_dir) = &self.cargo_target_dir {
+            Argument::new("", "--target-dir", Some(cargo_target_dir))
+        } else {
+            Argument::new("", "--target-dir", Some(CARGO_TARGET_DIR_DEFAULT_LIBFUZZER))
+        }
+    }
+    pub fn get_workspace_corpus(&self) -> Argument {
+        // libfuzzer_workspace_corpus
+        if let Some(corpus) = &self.libfuzzer_workspace_corpus {
+            Argument::new("", "", Some(corpus))
+        } else {
+            Argument::new("", "", Some(LIBFUZZER_WORKSPACE_DEFAULT_CORPUS))
+        }
+    }
+    pub fn get_workspace_artifacts(&self) -> Argument {
+        // libfuzzer_workspace_artifacts
+        if let Some(artifacts) = &self.libfuzzer_workspace_artifacts {
+            Argument::new("-artifact_prefix", "", Some(artifacts))
+        } else {
+            Argument::new(
+                "-artifact_prefix",
+                "",
+                Some(LIBFUZZER_WORKSPACE_DEFAULT_ARTIFACTS),
+            )
+        }
+    }
+    pub fn get_iterations(&self) -> Option<Argument> {
+        // runs
+        self.iterations
+            .filter(|iterations| *iterations > 0)
+            .map(|iterations| Argument::new("-runs", "", Some(&iterations.to_string())))
+    }
+    pub fn get_run_time(&self) -> Option<Argument> {
+        // seconds
+        self.run_time
+            .filter(|run_time| *run_time > 0)
+            .map(|run_time| Argument::new("-max_total_time", "", Some(&run_time.to_string())))
+    }
+    pub fn get_timeout(&self) -> Option<Argument> {
+        // seconds
+        self.timeout
+            .filter(|timeout| *timeout > 0)
+            .map(|timeout| Argument::new("-timeout", "", Some(&timeout.to_string())))
+    }
+    pub fn get_max_len(&self) -> Option<Argument> {
+        // bytes
+        self.max_len
+            .filter(|max_len| *max_len > 0)
+            .map(|max_len| Argument::new("-max_len", "", Some(&max_len.to_string())))
+    }
+    pub fn get_jobs(&self) -> Option<Argument> {
+        // jobs
+        self.jobs
+            .filter(|jobs| *jobs > 0)
+            .map(|jobs| Argument::new("-jobs", "", Some(&jobs.to_string())))
+    }
+    pub fn get_workers(&self) -> Option<Argument> {
+        // workers
+        self.workers
+            .filter(|workers| *workers > 0)
+            .map(|workers| Argument::new("-workers", "", Some(&workers.to_string())))
+    }
+    pub fn get_fork(&self) -> Option<Argument> {
+        // fork
+        self.fork
+            .filter(|fork| *fork > 0)
+            .map(|fork| Argument::new("-fork", "", Some(&fork.to_string())))
+    }
+    pub fn get_dict(&self) -> Option<Argument> {
+        // dictionary file
+        self.dict
+            .as_ref()
+            .filter(|dict| !dict.is_empty())
+            .map(|dict| Argument::new("-dict", "", resolve_path(dict).to_str()))
+    }
+    pub fn get_collect_fuzz_args(&self) -> Vec<String> {
+        let mut result = vec![];
+
+        if let Some(runs) = self.get_iterations() {
+            result.push(arg_to_flag(&runs));
+        }
+        if let Some(max_total_time) = self.get_run_time() {
+            result.push(arg_to_flag(&max_total_time));
+        }
+        if let Some(timeout) = self.get_timeout() {
+            result.push(arg_to_flag(&timeout));
+        }
+        if let Some(max_len) = self.get_max_len() {
+            result.push(arg_to_flag(&max_len));
+        }
+        if let Some(jobs) = self.get_jobs() {
+            result.push(arg_to_flag(&jobs));
+        }
+        if let Some(workers) = self.get_workers() {
+            result.push(arg_to_flag(&workers));
+        }
+        if let Some(fork) = self.get_fork() {
+            result.push(arg_to_flag(&fork));
+        }
+        if let Some(dict) = self.get_dict() {
+            result.push(arg_to_flag(&dict));
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl LibFuzzer {
+        fn clean() -> Self {
+            Self {
+                cargo_target_dir: None,
+                libfuzzer_workspace_corpus: None,
+                libfuzzer_workspace_artifacts: None,
+                iterations: None,
+                run_time: None,
+                timeout: None,
+                max_len: None,
+                jobs: None,
+                workers: None,
+                fork: None,
+                dict: None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_cargo_target_dir() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        let target_dir = "/foo/bar".to_string();
+
+        libfuzzer.cargo_target_dir = Some(target_dir);
+
+        let arg = libfuzzer.get_cargo_target_dir();
+        assert_eq!(arg, Argument::new("", "--target-dir", Some("/foo/bar")));
+    }
+    #[test]
+    fn test_workspace_corpus() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        // libfuzzer_workspace_corpus
+        libfuzzer.libfuzzer_workspace_corpus = Some("/foo/bar/dead/beef".to_string());
+
+        let arg = libfuzzer.get_workspace_corpus();
+        assert_eq!(arg, Argument::new("", "", Some("/foo/bar/dead/beef")));
+    }
+    #[test]
+    fn test_workspace_artifacts() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        // libfuzzer_workspace_artifacts
+        libfuzzer.libfuzzer_workspace_artifacts = Some("/foo/bar/artifacts".to_string());
+
+        let arg = libfuzzer.get_workspace_artifacts();
+        assert_eq!(
+            arg,
+            Argument::new("-artifact_prefix", "", Some("/foo/bar/artifacts"))
+        );
+    }
+    #[test]
+    fn test_iterations() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        // runs
+        libfuzzer.iterations = Some(555);
+
+        let arg = libfuzzer.get_collect_fuzz_args();
+        assert_eq!(arg, vec!["-runs=555"]);
+    }
+    #[test]
+    fn test_run_time() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        // seconds
+        libfuzzer.run_time = Some(15);
+
+        let arg = libfuzzer.get_collect_fuzz_args();
+        assert_eq!(arg, vec!["-max_total_time=15"]);
+    }
+    #[test]
+    fn test_zero_values_are_ignored() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        libfuzzer.iterations = Some(0);
+        libfuzzer.run_time = Some(0);
+        libfuzzer.jobs = Some(0);
+        libfuzzer.fork = Some(0);
+        libfuzzer.dict = Some("".to_string());
+
+        let arg = libfuzzer.get_collect_fuzz_args();
+        assert!(arg.is_empty());
+    }
+    #[test]
+    fn test_fork() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        // fork
+        libfuzzer.fork = Some(8);
+
+        let arg = libfuzzer.get_collect_fuzz_args();
+        assert_eq!(arg, vec!["-fork=8"]);
+    }
+    #[test]
+    fn test_dict() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        // dictionary file
+        libfuzzer.dict = Some("/foo/bar/fuzz.dict".to_string());
+
+        let arg = libfuzzer.get_collect_fuzz_args();
+        assert_eq!(arg, vec!["-dict=/foo/bar/fuzz.dict"]);
+    }
+    #[test]
+    fn test_all_fuzz_args() {
+        let mut libfuzzer = LibFuzzer::clean();
+
+        libfuzzer.iterations = Some(1000);
+        libfuzzer.run_time = Some(20);
+        libfuzzer.timeout = Some(5);
+        libfuzzer.max_len = Some(4096);
+        libfuzzer.jobs = Some(4);
+        libfuzzer.workers = Some(2);
+        libfuzzer.fork = Some(3);
+        libfuzzer.dict = Some("/foo/bar/fuzz.dict".to_string());
+
+        let arg = libfuzzer.get_collect_fuzz_args();
+        assert_eq!(
+            arg,
+            vec![
+                "-runs=1000",
+                "-max_total_time=20",
+                "-timeout=5",
+                "-max_len=4096",
+                "-jobs=4",
+                "-workers=2",
+                "-fork=3",
+                "-dict=/foo/bar/fuzz.dict"
+            ]
+        );
+    }
+}
@@ -28,6 +28,20 @@ pub(crate) fn arg_to_string(arg: &Argument) -> Vec<String> {
     }
 }
 
+/// libFuzzer expects the options in the `-flag=value` form.
+pub(crate) fn arg_to_flag(arg: &Argument) -> String {
+    let opt = arg
+        .short_opt
+        .as_ref()
+        .or(arg.long_opt.as_ref())
+        .cloned()
+        .unwrap_or_default();
+    match &arg.value {
+        Some(value) => format!("{}={}", opt, value),
+        None => opt,
+    }
+}
+
 /// Tries to find the root directory with the `Anchor.toml` file.
 /// Throws an error when there is no directory with the `Anchor.toml` file
 pub fn discover_root() -> Result<PathBuf, Error> {
@@ -17,6 +17,13 @@ pub fn init(_attr: TokenStream, item: TokenStream) -> TokenStream {
     quote::quote!(#input_fn).into()
 }
 
+/// Marks an associated function checking invariants after every successfully executed transaction
+#[proc_macro_attribute]
+pub fn invariant(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input_fn = parse_macro_input!(item as ItemFn);
+    quote::quote!(#input_fn).into()
+}
+
 /// Marks a flow method to be skipped during execution
 #[proc_macro_attribute]
 pub fn flow_ignore(_attr: TokenStream, item: TokenStream) -> TokenStream {
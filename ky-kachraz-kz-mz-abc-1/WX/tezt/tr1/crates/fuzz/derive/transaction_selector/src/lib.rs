@@ -4,7 +4,7 @@ use syn::parse_macro_input;
 use syn::DeriveInput;
 use trident_syn::parser::trident_transaction_selector::parse_trident_selector;
 
-#[proc_macro_derive(TransactionSelector)]
+#[proc_macro_derive(TransactionSelector, attributes(weight))]
 pub fn trident_fuzz_test_executor(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
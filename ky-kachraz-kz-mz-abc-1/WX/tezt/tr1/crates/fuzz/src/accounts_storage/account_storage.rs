@@ -3,19 +3,31 @@ use std::collections::HashMap;
 use solana_sdk::account::AccountSharedData;
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
 use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
 
 use crate::traits::FuzzClient;
 use crate::types::AccountId;
 
+use super::derive_address;
+use super::derive_keypair;
 use super::derive_pda;
+use super::malicious_account::is_malicious_account;
+use super::malicious_account::register_malicious_account;
+use super::malicious_account::AccountForgery;
+use super::next_storage_name;
+use super::register_signer;
 use super::AccountMetadata;
 use super::PdaSeeds;
 
 pub struct AccountsStorage {
     accounts: HashMap<AccountId, Pubkey>,
+    /// Keypairs of the accounts which are not PDAs, so they can sign the transactions.
+    keypairs: HashMap<AccountId, Keypair>,
     _max_accounts: u8,
+    /// Name the addresses of the accounts are derived from.
+    name: String,
 }
 
 impl Default for AccountsStorage {
@@ -25,11 +37,21 @@ impl Default for AccountsStorage {
 }
 
 impl AccountsStorage {
+    /// Creates a storage named after the order in which the storages are created,
+    /// i.e. the order of the fields of `FuzzAccounts`.
     pub fn new(max_accounts: u8) -> Self {
+        Self::named(&next_storage_name(), max_accounts)
+    }
+
+    /// Creates a storage with the given name, the addresses of its accounts do not
+    /// depend on the order in which the storages are created.
+    pub fn named(name: &str, max_accounts: u8) -> Self {
         let accounts: HashMap<AccountId, Pubkey> = HashMap::new();
         Self {
             accounts,
+            keypairs: HashMap::new(),
             _max_accounts: max_accounts,
+            name: name.to_string(),
         }
     }
 
@@ -37,6 +59,23 @@ impl AccountsStorage {
         self.accounts.is_empty()
     }
 
+    /// Returns the address of the account, if it was already created.
+    pub fn get(&self, account_id: AccountId) -> Option<Pubkey> {
+        self.accounts.get(&account_id).copied()
+    }
+
+    /// Returns the addresses of all created accounts ordered by their account ids.
+    pub fn addresses(&self) -> Vec<Pubkey> {
+        let mut accounts: Vec<_> = self.accounts.iter().collect();
+        accounts.sort_by_key(|(account_id, _)| **account_id);
+        accounts.into_iter().map(|(_, address)| *address).collect()
+    }
+
+    /// Returns the keypair of the account, if the account was created without `PdaSeeds`.
+    pub fn get_keypair(&self, account_id: AccountId) -> Option<Keypair> {
+        self.keypairs.get(&account_id).map(Keypair::insecure_clone)
+    }
+
     pub fn get_or_create(
         &mut self,
         account_id: AccountId,
@@ -47,7 +86,7 @@ impl AccountsStorage {
         match self.accounts.get(&account_id) {
             Some(address) => *address,
             None => {
-                let address = self.get_or_create_address(seeds);
+                let address = self.get_or_create_address(account_id, seeds);
 
                 let metadata = match account_metadata {
                     Some(metadata) => metadata,
@@ -71,6 +110,42 @@ impl AccountsStorage {
         }
     }
 
+    /// Forges the account on the address in place with the forgery decoded from the fuzzer
+    /// data, see [`AccountForgery::from_byte`]. The original account is restored once the
+    /// transaction is processed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn forge_malicious_account(
+        &mut self,
+        forgery: Option<AccountForgery>,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        address: Pubkey,
+        is_writable: bool,
+        field: &str,
+    ) {
+        let Some(forgery) = forgery.filter(|forgery| forgery.applies_to(is_writable)) else {
+            return;
+        };
+
+        // the account passed to multiple fields is forged only once
+        if is_malicious_account(&address) {
+            return;
+        }
+
+        let wrong_owner = derive_address(&format!("{}_malicious", self.name), account_id);
+
+        let account = client.get_account(&address);
+        let forged = forgery.forge(&account, &wrong_owner, &client.get_sysvar::<Rent>());
+
+        // the forgery has no effect on the account, e.g. truncation of empty data
+        if forged == account {
+            return;
+        }
+
+        client.set_account_custom(&address, &forged);
+        register_malicious_account(address, field, forgery, account, &forged);
+    }
+
     #[cfg(feature = "token")]
     #[allow(clippy::too_many_arguments)]
     pub fn get_or_create_token_account(
@@ -89,7 +164,7 @@ impl AccountsStorage {
         match self.accounts.get(&account_id) {
             Some(address) => *address,
             None => {
-                let address = self.get_or_create_address(seeds);
+                let address = self.get_or_create_address(account_id, seeds);
 
                 // If account on the address is already initialized, we dont override it
                 if client.get_account(&address) == AccountSharedData::default() {
@@ -128,7 +203,7 @@ impl AccountsStorage {
         match self.accounts.get(&account_id) {
             Some(address) => *address,
             None => {
-                let address = self.get_or_create_address(seeds);
+                let address = self.get_or_create_address(account_id, seeds);
 
                 // If account on the address is already initialized, we dont override it
                 if client.get_account(&address) == AccountSharedData::default() {
@@ -160,7 +235,7 @@ impl AccountsStorage {
         match self.accounts.get(&account_id) {
             Some(address) => *address,
             None => {
-                let address = self.get_or_create_address(seeds);
+                let address = self.get_or_create_address(account_id, seeds);
 
                 // If account on the address is already initialized, we dont override it
                 if client.get_account(&address) == AccountSharedData::default() {
@@ -199,7 +274,7 @@ impl AccountsStorage {
         match self.accounts.get(&account_id) {
             Some(address) => *address,
             None => {
-                let address = self.get_or_create_address(seeds);
+                let address = self.get_or_create_address(account_id, seeds);
 
                 // If account on the address is already initialized, we dont override it
                 if client.get_account(&address) == AccountSharedData::default() {
@@ -230,7 +305,7 @@ impl AccountsStorage {
         match self.accounts.get(&account_id) {
             Some(address) => *address,
             None => {
-                let address = self.get_or_create_address(seeds);
+                let address = self.get_or_create_address(account_id, seeds);
 
                 // If account on the address is already initialized, we dont override it
                 if client.get_account(&address) == AccountSharedData::default() {
@@ -252,16 +327,24 @@ impl AccountsStorage {
         }
     }
 
-    fn get_or_create_address(&self, seeds: Option<PdaSeeds>) -> Pubkey {
+    fn get_or_create_address(&mut self, account_id: AccountId, seeds: Option<PdaSeeds>) -> Pubkey {
         match seeds {
             Some(seeds) => {
                 if let Some(pubkey) = derive_pda(seeds.seeds, &seeds.program_id) {
                     pubkey
                 } else {
-                    Pubkey::new_unique()
+                    derive_address(&self.name, account_id)
                 }
             }
-            None => Keypair::new().pubkey(),
+            None => {
+                let keypair = derive_keypair(&self.name, account_id);
+                let address = keypair.pubkey();
+
+                register_signer(&keypair);
+                self.keypairs.insert(account_id, keypair);
+
+                address
+            }
         }
     }
 }
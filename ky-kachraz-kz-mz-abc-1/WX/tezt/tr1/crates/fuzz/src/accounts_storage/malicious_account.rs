@@ -0,0 +1,383 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use solana_sdk::account::{AccountSharedData, ReadableAccount, WritableAccount};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+
+use crate::traits::FuzzClient;
+
+/// Accounts of the fields with the `malicious` constraint are forged if the forgery byte
+/// decoded with the account is at least `FORGERY_THRESHOLD`, i.e. one in eight accounts.
+const FORGERY_THRESHOLD: u8 = 224;
+
+thread_local! {
+    /// Accounts forged for the current transaction, keyed by their addresses.
+    static MALICIOUS_ACCOUNTS: RefCell<HashMap<Pubkey, MaliciousAccount>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Account forged in place for the duration of the transaction.
+struct MaliciousAccount {
+    /// Field of the transaction the account was forged for.
+    field: String,
+    forgery: AccountForgery,
+    /// The well-formed account, `None` once it was restored.
+    original: Option<AccountSharedData>,
+    /// Lamports of the forged account before the transaction.
+    forged_lamports: u64,
+    /// Whether the transaction changed the lamports of the forged account, known once restored.
+    lamports_changed: bool,
+}
+
+/// Way in which a malicious account differs from the well-formed account it is copied from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountForgery {
+    /// The account is owned by an unrelated program.
+    WrongOwner,
+    /// The first 8 bytes of the data, i.e. the Anchor discriminator, are inverted.
+    WrongDiscriminator,
+    /// The data is truncated to the half of its length.
+    TruncatedData,
+    /// The account holds one lamport less than the rent exemption requires. Applied only
+    /// to writable accounts and reported only if the transaction changes their lamports,
+    /// as programs do not have to check the rent exemption of the accounts they only read.
+    BelowRentExemption,
+}
+
+impl AccountForgery {
+    /// Selects the forgery by the byte decoded from the fuzzer data, `None` means the account
+    /// is well-formed. The exhausted fuzzer data decodes to zero, i.e. the well-formed account.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        if byte < FORGERY_THRESHOLD {
+            return None;
+        }
+        match byte % 4 {
+            0 => Some(AccountForgery::WrongOwner),
+            1 => Some(AccountForgery::WrongDiscriminator),
+            2 => Some(AccountForgery::TruncatedData),
+            _ => Some(AccountForgery::BelowRentExemption),
+        }
+    }
+
+    /// Whether the forgery can be applied to the account passed to the transaction.
+    pub(crate) fn applies_to(&self, is_writable: bool) -> bool {
+        !matches!(self, AccountForgery::BelowRentExemption) || is_writable
+    }
+
+    /// Returns the copy of the account with the forgery applied.
+    pub(crate) fn forge(
+        &self,
+        account: &AccountSharedData,
+        wrong_owner: &Pubkey,
+        rent: &Rent,
+    ) -> AccountSharedData {
+        let mut forged = account.clone();
+        match self {
+            AccountForgery::WrongOwner => forged.set_owner(*wrong_owner),
+            AccountForgery::WrongDiscriminator => {
+                let data = forged.data_as_mut_slice();
+                let discriminator_len = data.len().min(8);
+                data[..discriminator_len]
+                    .iter_mut()
+                    .for_each(|byte| *byte = !*byte);
+            }
+            AccountForgery::TruncatedData => {
+                let data = account.data();
+                forged.set_data_from_slice(&data[..data.len() / 2]);
+            }
+            AccountForgery::BelowRentExemption => {
+                forged.set_lamports(rent.minimum_balance(account.data().len()).saturating_sub(1));
+            }
+        }
+        forged
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            AccountForgery::WrongOwner => "a wrong owner",
+            AccountForgery::WrongDiscriminator => "a wrong discriminator",
+            AccountForgery::TruncatedData => "truncated data",
+            AccountForgery::BelowRentExemption => "lamports below the rent exemption",
+        }
+    }
+}
+
+/// Remembers the malicious account, so the transactions accepting it can be reported
+/// and the original account can be restored after the transaction.
+pub(crate) fn register_malicious_account(
+    address: Pubkey,
+    field: &str,
+    forgery: AccountForgery,
+    original: AccountSharedData,
+    forged: &AccountSharedData,
+) {
+    MALICIOUS_ACCOUNTS.with(|accounts| {
+        accounts.borrow_mut().insert(
+            address,
+            MaliciousAccount {
+                field: field.to_string(),
+                forgery,
+                original: Some(original),
+                forged_lamports: forged.lamports(),
+                lamports_changed: false,
+            },
+        )
+    });
+}
+
+/// Whether the account on the address is already forged for the current transaction.
+pub(crate) fn is_malicious_account(address: &Pubkey) -> bool {
+    MALICIOUS_ACCOUNTS.with(|accounts| {
+        accounts
+            .borrow()
+            .get(address)
+            .is_some_and(|malicious| malicious.original.is_some())
+    })
+}
+
+/// Returns the description of the first malicious account passed to the instructions.
+/// The account below the rent exemption is reported only if its lamports were changed.
+pub(crate) fn find_malicious_account(instructions: &[Instruction]) -> Option<String> {
+    MALICIOUS_ACCOUNTS.with(|accounts| {
+        let accounts = accounts.borrow();
+        instructions
+            .iter()
+            .flat_map(|instruction| instruction.accounts.iter())
+            .filter_map(|meta| accounts.get(&meta.pubkey))
+            .find(|malicious| {
+                malicious.forgery != AccountForgery::BelowRentExemption
+                    || malicious.lamports_changed
+            })
+            .map(|malicious| {
+                format!(
+                    "the `{}` account with {}",
+                    malicious.field,
+                    malicious.forgery.describe()
+                )
+            })
+    })
+}
+
+/// Writes the original accounts back in place of the forged ones. The malicious accounts
+/// are still reported by [`find_malicious_account`] until they are cleared.
+pub(crate) fn restore_malicious_accounts(client: &mut impl FuzzClient) {
+    MALICIOUS_ACCOUNTS.with(|accounts| {
+        for (address, malicious) in accounts.borrow_mut().iter_mut() {
+            if let Some(original) = malicious.original.take() {
+                malicious.lamports_changed =
+                    client.get_account(address).lamports() != malicious.forged_lamports;
+                client.set_account_custom(address, &original);
+            }
+        }
+    });
+}
+
+/// Forgets the malicious accounts which were already restored, i.e. the accounts
+/// forged for the previously executed transactions.
+pub(crate) fn forget_restored_malicious_accounts() {
+    MALICIOUS_ACCOUNTS.with(|accounts| {
+        accounts
+            .borrow_mut()
+            .retain(|_, malicious| malicious.original.is_some())
+    });
+}
+
+/// Forgets all malicious accounts.
+pub(crate) fn clear_malicious_accounts() {
+    MALICIOUS_ACCOUNTS.with(|accounts| accounts.borrow_mut().clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(data: &[u8]) -> AccountSharedData {
+        let mut account = AccountSharedData::new(5_000_000, data.len(), &Pubkey::new_unique());
+        account.set_data_from_slice(data);
+        account
+    }
+
+    #[test]
+    fn test_from_byte() {
+        let forgeries: Vec<_> = (0..=u8::MAX)
+            .filter_map(AccountForgery::from_byte)
+            .collect();
+        assert_eq!(forgeries.len(), 32);
+        for forgery in [
+            AccountForgery::WrongOwner,
+            AccountForgery::WrongDiscriminator,
+            AccountForgery::TruncatedData,
+            AccountForgery::BelowRentExemption,
+        ] {
+            assert_eq!(forgeries.iter().filter(|f| **f == forgery).count(), 8);
+        }
+        // the exhausted fuzzer data gives the well-formed account
+        assert_eq!(AccountForgery::from_byte(0), None);
+    }
+
+    #[test]
+    fn test_below_rent_exemption_applies_to_writable_accounts() {
+        assert!(AccountForgery::BelowRentExemption.applies_to(true));
+        assert!(!AccountForgery::BelowRentExemption.applies_to(false));
+        assert!(AccountForgery::WrongOwner.applies_to(false));
+    }
+
+    #[test]
+    fn test_forge_wrong_owner() {
+        let original = account(&[1, 2, 3]);
+        let wrong_owner = Pubkey::new_unique();
+
+        let forged = AccountForgery::WrongOwner.forge(&original, &wrong_owner, &Rent::default());
+
+        assert_eq!(forged.owner(), &wrong_owner);
+        assert_eq!(forged.data(), original.data());
+        assert_eq!(forged.lamports(), original.lamports());
+    }
+
+    #[test]
+    fn test_forge_wrong_discriminator() {
+        let original = account(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let forged = AccountForgery::WrongDiscriminator.forge(
+            &original,
+            &Pubkey::default(),
+            &Rent::default(),
+        );
+
+        assert_eq!(
+            forged.data(),
+            &[255, 254, 253, 252, 251, 250, 249, 248, 8, 9]
+        );
+        assert_eq!(forged.owner(), original.owner());
+    }
+
+    #[test]
+    fn test_forge_wrong_discriminator_of_short_data() {
+        let original = account(&[0, 1, 2]);
+
+        let forged = AccountForgery::WrongDiscriminator.forge(
+            &original,
+            &Pubkey::default(),
+            &Rent::default(),
+        );
+
+        assert_eq!(forged.data(), &[255, 254, 253]);
+    }
+
+    #[test]
+    fn test_forge_truncated_data() {
+        let original = account(&[0, 1, 2, 3, 4]);
+
+        let forged =
+            AccountForgery::TruncatedData.forge(&original, &Pubkey::default(), &Rent::default());
+
+        assert_eq!(forged.data(), &[0, 1]);
+        assert_eq!(forged.owner(), original.owner());
+        assert_eq!(forged.lamports(), original.lamports());
+    }
+
+    #[test]
+    fn test_forge_below_rent_exemption() {
+        let original = account(&[0; 100]);
+        let rent = Rent::default();
+
+        let forged = AccountForgery::BelowRentExemption.forge(&original, &Pubkey::default(), &rent);
+
+        assert_eq!(forged.lamports(), rent.minimum_balance(100) - 1);
+        assert!(!rent.is_exempt(forged.lamports(), forged.data().len()));
+        assert_eq!(forged.data(), original.data());
+    }
+
+    #[test]
+    fn test_forge_keeps_empty_data() {
+        let original = account(&[]);
+
+        let forged =
+            AccountForgery::TruncatedData.forge(&original, &Pubkey::default(), &Rent::default());
+
+        assert_eq!(forged, original);
+    }
+
+    #[test]
+    fn test_restored_accounts_are_forgotten() {
+        let address = Pubkey::new_unique();
+        let instructions = [Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![solana_sdk::instruction::AccountMeta::new(address, false)],
+        )];
+        let original = account(&[1, 2, 3]);
+        register_malicious_account(
+            address,
+            "vault",
+            AccountForgery::WrongOwner,
+            original.clone(),
+            &original,
+        );
+        assert!(is_malicious_account(&address));
+
+        // the restored account is still reported for the executed transaction,
+        // but it can be forged again for the next one
+        MALICIOUS_ACCOUNTS
+            .with(|accounts| accounts.borrow_mut().get_mut(&address).unwrap().original = None);
+        assert!(!is_malicious_account(&address));
+        assert_eq!(
+            find_malicious_account(&instructions).as_deref(),
+            Some("the `vault` account with a wrong owner")
+        );
+
+        forget_restored_malicious_accounts();
+        assert_eq!(find_malicious_account(&instructions), None);
+    }
+
+    #[test]
+    fn test_below_rent_exemption_reported_if_lamports_changed() {
+        let mut client = trident_svm::trident_svm::TridentSVM::new(&[], &[], &[]);
+        let address = Pubkey::new_unique();
+        let instructions = [Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![solana_sdk::instruction::AccountMeta::new(address, false)],
+        )];
+        let original = account(&[0; 100]);
+        let forged = AccountForgery::BelowRentExemption.forge(
+            &original,
+            &Pubkey::default(),
+            &Rent::default(),
+        );
+
+        // the program accepts the account without touching its lamports
+        client.set_account_custom(&address, &forged);
+        register_malicious_account(
+            address,
+            "vault",
+            AccountForgery::BelowRentExemption,
+            original.clone(),
+            &forged,
+        );
+        restore_malicious_accounts(&mut client);
+        assert_eq!(find_malicious_account(&instructions), None);
+        assert_eq!(FuzzClient::get_account(&mut client, &address), original);
+        clear_malicious_accounts();
+
+        // the program withdraws from the account
+        let mut withdrawn = forged.clone();
+        withdrawn.set_lamports(forged.lamports() - 1);
+        client.set_account_custom(&address, &withdrawn);
+        register_malicious_account(
+            address,
+            "vault",
+            AccountForgery::BelowRentExemption,
+            original.clone(),
+            &forged,
+        );
+        restore_malicious_accounts(&mut client);
+        assert_eq!(
+            find_malicious_account(&instructions).as_deref(),
+            Some("the `vault` account with lamports below the rent exemption")
+        );
+        clear_malicious_accounts();
+    }
+}
@@ -1,4 +1,5 @@
 pub mod account_storage;
+pub mod malicious_account;
 
 #[cfg(feature = "token")]
 mod mint_account;
@@ -12,7 +13,32 @@ mod token_account;
 #[cfg(feature = "vote")]
 mod vote_account;
 
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use solana_sdk::hash::hashv;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::keypair::keypair_from_seed;
+use solana_sdk::signer::Signer;
+
+use crate::types::AccountId;
+
+/// Seed of the fuzzing campaign mixed into all derived addresses, see [`set_address_seed`].
+static ADDRESS_SEED: OnceLock<String> = OnceLock::new();
+
+thread_local! {
+    /// Number of unnamed storages created since the last [`reset_storage_names`],
+    /// the storages are named after the order in which they were created.
+    static STORAGE_COUNT: Cell<usize> = const { Cell::new(0) };
+
+    /// Keypairs of the accounts created by the storages, the client signs the transactions with them.
+    static SIGNERS: RefCell<HashMap<Pubkey, Keypair>> = RefCell::new(HashMap::new());
+
+    /// Keypairs registered with `FuzzClient::add_signer`, kept for the whole fuzzing session.
+    static CLIENT_SIGNERS: RefCell<HashMap<Pubkey, Keypair>> = RefCell::new(HashMap::new());
+}
 
 pub struct AccountMetadata {
     pub lamports: u64,
@@ -48,3 +74,93 @@ fn derive_pda(seeds: &[&[u8]], program_id: &Pubkey) -> Option<Pubkey> {
         None
     }
 }
+
+/// Sets the seed of the fuzzing campaign, so the derived addresses differ between campaigns
+/// with different seeds. Only the first call has an effect, later calls are ignored.
+pub fn set_address_seed(seed: &str) {
+    let _ = ADDRESS_SEED.set(seed.to_string());
+}
+
+/// Restarts the naming of the unnamed storages. It is called before the accounts are created
+/// for every fuzzing iteration, so the storages get the same names in every iteration.
+#[doc(hidden)]
+pub fn reset_storage_names() {
+    STORAGE_COUNT.with(|count| count.set(0));
+}
+
+fn next_storage_name() -> String {
+    STORAGE_COUNT.with(|count| {
+        let index = count.get();
+        count.set(index + 1);
+        format!("storage_{}", index)
+    })
+}
+
+/// Derives the keypair of the account deterministically from the campaign seed, the name
+/// of the storage and the account id, so replaying a crash file yields the same addresses.
+pub(crate) fn derive_keypair(storage_name: &str, account_id: AccountId) -> Keypair {
+    let seed = ADDRESS_SEED.get().map(String::as_str).unwrap_or_default();
+    let hash = hashv(&[
+        b"trident-keypair",
+        seed.as_bytes(),
+        storage_name.as_bytes(),
+        &[account_id],
+    ]);
+    keypair_from_seed(hash.as_ref()).expect("32 bytes are a valid keypair seed")
+}
+
+/// Deterministic replacement of a PDA which could not be derived from the seeds.
+fn derive_address(storage_name: &str, account_id: AccountId) -> Pubkey {
+    let seed = ADDRESS_SEED.get().map(String::as_str).unwrap_or_default();
+    let hash = hashv(&[
+        b"trident-address",
+        seed.as_bytes(),
+        storage_name.as_bytes(),
+        &[account_id],
+    ]);
+    Pubkey::new_from_array(hash.to_bytes())
+}
+
+/// Makes the keypair available to the client for signing the transactions.
+pub(crate) fn register_signer(keypair: &Keypair) {
+    SIGNERS.with(|signers| {
+        signers
+            .borrow_mut()
+            .insert(keypair.pubkey(), keypair.insecure_clone())
+    });
+}
+
+/// Makes the keypair available to the client for signing the transactions in all
+/// fuzzing iterations, unlike the keypairs of the storages it is not cleared.
+pub(crate) fn register_client_signer(keypair: &Keypair) {
+    CLIENT_SIGNERS.with(|signers| {
+        signers
+            .borrow_mut()
+            .insert(keypair.pubkey(), keypair.insecure_clone())
+    });
+}
+
+/// Returns the keypairs of the given addresses known to the storages or registered
+/// with the client, the addresses without a keypair are skipped.
+pub(crate) fn get_signers<'a>(addresses: impl IntoIterator<Item = &'a Pubkey>) -> Vec<Keypair> {
+    SIGNERS.with(|signers| {
+        CLIENT_SIGNERS.with(|client_signers| {
+            let signers = signers.borrow();
+            let client_signers = client_signers.borrow();
+            addresses
+                .into_iter()
+                .filter_map(|address| {
+                    signers
+                        .get(address)
+                        .or_else(|| client_signers.get(address))
+                        .map(Keypair::insecure_clone)
+                })
+                .collect()
+        })
+    })
+}
+
+/// Forgets the keypairs of the accounts created during the fuzzing iteration.
+pub(crate) fn clear_signers() {
+    SIGNERS.with(|signers| signers.borrow_mut().clear());
+}
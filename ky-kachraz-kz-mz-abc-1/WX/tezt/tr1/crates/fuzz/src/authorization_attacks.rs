@@ -0,0 +1,356 @@
+use std::sync::OnceLock;
+
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::WritableAccount;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::accounts_storage::{derive_keypair, register_signer};
+use crate::replay_log::{record_transaction, TransactionOutcome};
+use crate::traits::{FuzzClient, TransactionGetters};
+
+/// Whether the authorization attacks are performed, see [`set_authorization_attacks`].
+static AUTHORIZATION_ATTACKS: OnceLock<bool> = OnceLock::new();
+
+/// Name of the storage the attacker keypairs are derived from.
+const ATTACKER_STORAGE_NAME: &str = "trident_attacker";
+
+/// Indices of the attacker keypairs, the unrelated signer, the address of the substituted
+/// account and the attacker-controlled program owning it.
+const ATTACKER_SIGNER_INDEX: u8 = 0;
+const ATTACKER_ACCOUNT_INDEX: u8 = 1;
+const ATTACKER_PROGRAM_INDEX: u8 = 2;
+
+/// Attack on the authorization of an account performed before every transaction
+/// if the authorization attacks are enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountAttack {
+    /// The signer is replaced with an unrelated keypair.
+    UnrelatedSigner,
+    /// The signer is passed without its signature.
+    MissingSignature,
+    /// The account constrained by `address` or `seeds` is replaced with a copy of the
+    /// original account, owner included, on an attacker-controlled address.
+    AccountCopy,
+    /// The account constrained by `address` or `seeds` is replaced with an account on an
+    /// attacker-controlled address holding the data of the original account and owned
+    /// by an attacker-controlled program.
+    AttackerOwnedAccount,
+}
+
+/// Attacks applicable to one account of the instruction.
+#[derive(Debug, Clone)]
+pub struct AttackTarget {
+    pub account: &'static str,
+    pub attacks: Vec<AccountAttack>,
+}
+
+impl AttackTarget {
+    pub fn new(account: &'static str, attacks: Vec<AccountAttack>) -> Self {
+        Self { account, attacks }
+    }
+}
+
+impl AccountAttack {
+    fn describe(&self, account: &str) -> String {
+        match self {
+            AccountAttack::UnrelatedSigner => {
+                format!("an unrelated keypair signing as the `{}` account", account)
+            }
+            AccountAttack::MissingSignature => {
+                format!("the `{}` account not signing the transaction", account)
+            }
+            AccountAttack::AccountCopy => {
+                format!(
+                    "a copy of the `{}` account on an attacker-controlled address",
+                    account
+                )
+            }
+            AccountAttack::AttackerOwnedAccount => {
+                format!(
+                    "an attacker-owned account in place of the `{}` account",
+                    account
+                )
+            }
+        }
+    }
+
+    /// Returns the instructions with the attacked account, or `None` if the attack
+    /// cannot be performed on the account.
+    fn apply(
+        &self,
+        client: &mut impl FuzzClient,
+        instructions: &[Instruction],
+        instruction_index: usize,
+        account_index: usize,
+    ) -> Option<Vec<Instruction>> {
+        let mut attacked = instructions.to_vec();
+        let meta = attacked[instruction_index]
+            .accounts
+            .get(account_index)?
+            .clone();
+
+        // the payer signs every transaction, so its authorization cannot be attacked
+        if meta.pubkey == client.payer().pubkey() {
+            return None;
+        }
+
+        match self {
+            AccountAttack::UnrelatedSigner => {
+                let attacker = attacker_keypair(ATTACKER_SIGNER_INDEX);
+                register_signer(&attacker);
+                attacked[instruction_index].accounts[account_index].pubkey = attacker.pubkey();
+            }
+            AccountAttack::MissingSignature => {
+                // the account still signs if it is a signer elsewhere in the transaction
+                let signs_elsewhere = instructions.iter().enumerate().any(|(i, instruction)| {
+                    instruction.accounts.iter().enumerate().any(|(j, other)| {
+                        (i, j) != (instruction_index, account_index)
+                            && other.pubkey == meta.pubkey
+                            && other.is_signer
+                    })
+                });
+                if !meta.is_signer || signs_elsewhere {
+                    return None;
+                }
+                attacked[instruction_index].accounts[account_index].is_signer = false;
+            }
+            AccountAttack::AccountCopy | AccountAttack::AttackerOwnedAccount => {
+                let attacker = attacker_keypair(ATTACKER_ACCOUNT_INDEX);
+                if meta.is_signer {
+                    register_signer(&attacker);
+                }
+                let mut account = client.get_account(&meta.pubkey);
+                if let AccountAttack::AttackerOwnedAccount = self {
+                    account.set_owner(attacker_program());
+                }
+                client.set_account_custom(&attacker.pubkey(), &account);
+                attacked[instruction_index].accounts[account_index].pubkey = attacker.pubkey();
+            }
+        }
+
+        Some(attacked)
+    }
+
+    /// Removes the accounts written by the attack, so the attack does not change the state
+    /// the transaction and the following attacks are executed on.
+    fn revert(&self, client: &mut impl FuzzClient) {
+        if let AccountAttack::AccountCopy | AccountAttack::AttackerOwnedAccount = self {
+            // an empty account is indistinguishable from a non-existent one
+            client.set_account_custom(
+                &attacker_keypair(ATTACKER_ACCOUNT_INDEX).pubkey(),
+                &AccountSharedData::default(),
+            );
+        }
+    }
+}
+
+/// Enables the authorization attacks. Only the first call has an effect, later calls are ignored.
+pub fn set_authorization_attacks(enabled: bool) {
+    let _ = AUTHORIZATION_ATTACKS.set(enabled);
+}
+
+pub(crate) fn authorization_attacks_enabled() -> bool {
+    AUTHORIZATION_ATTACKS.get().copied().unwrap_or_default()
+}
+
+fn attacker_keypair(index: u8) -> Keypair {
+    derive_keypair(ATTACKER_STORAGE_NAME, index)
+}
+
+fn attacker_program() -> Pubkey {
+    attacker_keypair(ATTACKER_PROGRAM_INDEX).pubkey()
+}
+
+/// Executes every applicable attack on the accounts of the transaction and panics
+/// if any of the attacked transactions succeeds. The failed attacks do not change the state,
+/// the accounts written by the attacks are removed after every attempt.
+pub(crate) fn run_authorization_attacks(
+    client: &mut impl FuzzClient,
+    transaction: &(impl TransactionGetters + ?Sized),
+    instructions: &[Instruction],
+) {
+    let targets = transaction.get_instruction_attack_targets();
+
+    for (instruction_index, instruction_targets) in targets.iter().enumerate() {
+        for (account_index, target) in instruction_targets.iter().enumerate() {
+            for attack in &target.attacks {
+                let Some(attacked) =
+                    attack.apply(client, instructions, instruction_index, account_index)
+                else {
+                    continue;
+                };
+
+                let result = client.process_instructions(&attacked);
+                attack.revert(client);
+
+                if result.is_ok() {
+                    let message = format!(
+                        "Unauthorized access! The {} transaction succeeded with {}",
+                        transaction.get_transaction_name(),
+                        attack.describe(target.account)
+                    );
+
+                    record_transaction(
+                        transaction,
+                        &attacked,
+                        false,
+                        TransactionOutcome::InvariantFailed(message.clone()),
+                    );
+
+                    eprintln!(
+                        "\x1b[31mCRASH DETECTED!\x1b[0m Authorization attack on the {} transaction succeeded!",
+                        transaction.get_transaction_name()
+                    );
+                    panic!("{}", message)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use solana_sdk::account::ReadableAccount;
+    use solana_sdk::instruction::AccountMeta;
+    use trident_svm::trident_svm::TridentSVM;
+
+    use crate::accounts_storage::get_signers;
+
+    fn instruction(accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts)
+    }
+
+    #[test]
+    fn test_payer_is_not_attacked() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let payer = client.payer().pubkey();
+        let instructions = [instruction(vec![AccountMeta::new(payer, true)])];
+
+        for attack in [
+            AccountAttack::UnrelatedSigner,
+            AccountAttack::MissingSignature,
+            AccountAttack::AccountCopy,
+            AccountAttack::AttackerOwnedAccount,
+        ] {
+            assert_eq!(attack.apply(&mut client, &instructions, 0, 0), None);
+        }
+    }
+
+    #[test]
+    fn test_unrelated_signer() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let authority = Pubkey::new_unique();
+        let instructions = [instruction(vec![AccountMeta::new(authority, true)])];
+
+        let attacked = AccountAttack::UnrelatedSigner
+            .apply(&mut client, &instructions, 0, 0)
+            .unwrap();
+
+        let attacker = attacked[0].accounts[0].pubkey;
+        assert_ne!(attacker, authority);
+        assert!(attacked[0].accounts[0].is_signer);
+        // the transaction is signed by the attacker
+        assert_eq!(get_signers([&attacker]).len(), 1);
+    }
+
+    #[test]
+    fn test_missing_signature() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let authority = Pubkey::new_unique();
+        let instructions = [instruction(vec![AccountMeta::new(authority, true)])];
+
+        let attacked = AccountAttack::MissingSignature
+            .apply(&mut client, &instructions, 0, 0)
+            .unwrap();
+
+        assert_eq!(attacked[0].accounts[0].pubkey, authority);
+        assert!(!attacked[0].accounts[0].is_signer);
+    }
+
+    #[test]
+    fn test_missing_signature_of_account_signing_elsewhere() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let authority = Pubkey::new_unique();
+        let instructions = [
+            instruction(vec![AccountMeta::new(authority, true)]),
+            instruction(vec![AccountMeta::new_readonly(authority, true)]),
+        ];
+
+        assert_eq!(
+            AccountAttack::MissingSignature.apply(&mut client, &instructions, 0, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_missing_signature_of_non_signer() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let instructions = [instruction(vec![AccountMeta::new(
+            Pubkey::new_unique(),
+            false,
+        )])];
+
+        assert_eq!(
+            AccountAttack::MissingSignature.apply(&mut client, &instructions, 0, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_account_copy() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let config = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut account = AccountSharedData::new(1_000_000, 3, &owner);
+        account.set_data_from_slice(&[1, 2, 3]);
+        FuzzClient::set_account_custom(&mut client, &config, &account);
+        let instructions = [instruction(vec![AccountMeta::new_readonly(config, false)])];
+
+        let attacked = AccountAttack::AccountCopy
+            .apply(&mut client, &instructions, 0, 0)
+            .unwrap();
+
+        let attacker = attacked[0].accounts[0].pubkey;
+        assert_ne!(attacker, config);
+        assert_eq!(FuzzClient::get_account(&mut client, &attacker), account);
+
+        AccountAttack::AccountCopy.revert(&mut client);
+        assert_eq!(
+            FuzzClient::get_account(&mut client, &attacker),
+            AccountSharedData::default()
+        );
+    }
+
+    #[test]
+    fn test_attacker_owned_account() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let config = Pubkey::new_unique();
+        let mut account = AccountSharedData::new(1_000_000, 3, &Pubkey::new_unique());
+        account.set_data_from_slice(&[1, 2, 3]);
+        FuzzClient::set_account_custom(&mut client, &config, &account);
+        let instructions = [instruction(vec![AccountMeta::new(config, false)])];
+
+        let attacked = AccountAttack::AttackerOwnedAccount
+            .apply(&mut client, &instructions, 0, 0)
+            .unwrap();
+
+        let attacker = attacked[0].accounts[0].pubkey;
+        let substitute = FuzzClient::get_account(&mut client, &attacker);
+        assert_eq!(substitute.owner(), &attacker_program());
+        assert_eq!(substitute.data(), account.data());
+        assert_eq!(substitute.lamports(), account.lamports());
+        // the original account is not changed
+        assert_eq!(FuzzClient::get_account(&mut client, &config), account);
+
+        AccountAttack::AttackerOwnedAccount.revert(&mut client);
+        assert_eq!(
+            FuzzClient::get_account(&mut client, &attacker),
+            AccountSharedData::default()
+        );
+    }
+}
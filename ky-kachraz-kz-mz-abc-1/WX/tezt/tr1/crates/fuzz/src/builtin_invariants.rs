@@ -0,0 +1,552 @@
+use std::cell::RefCell;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use solana_sdk::account::{AccountSharedData, ReadableAccount};
+use solana_sdk::fee::FeeStructure;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use solana_sdk::transaction::TransactionError;
+
+use crate::error::FuzzingError;
+use crate::traits::FuzzClient;
+use crate::transaction_result::get_transaction_result;
+
+/// Maximum compute units the transaction can consume.
+const MAX_TRANSACTION_COMPUTE_UNITS: u64 = 1_400_000;
+
+/// Percentage of the transaction compute unit limit above which the transaction is flagged.
+const COMPUTE_UNIT_LIMIT_PERCENTAGE: u64 = 90;
+
+/// Logged by the runtime when the program runs out of compute units.
+const EXCEEDED_COMPUTE_UNITS_LOG: &str = "exceeded CUs meter";
+
+/// Compute units a single instruction is allowed to consume, checked by
+/// the compute units invariant, see [`set_compute_unit_threshold`].
+static COMPUTE_UNIT_THRESHOLD: OnceLock<Option<u64>> = OnceLock::new();
+
+thread_local! {
+    /// Built-in invariants checked after every successful transaction.
+    static BUILTIN_INVARIANTS: RefCell<Vec<BuiltinInvariant>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Ready-made invariant checked after every successful transaction on all accounts
+/// referenced by the transaction, including the payer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinInvariant {
+    /// The total lamports of the accounts do not change, except for the transaction fee.
+    LamportConservation,
+    /// Writable program-owned accounts which were rent exempt stay rent exempt.
+    RentExemption,
+    /// Accounts closed by the transaction have zeroed data and are owned by the system program.
+    ClosedAccounts,
+    /// Data and lamports of read-only accounts do not change.
+    ReadonlyAccounts,
+    /// No instruction consumes more compute units than the configured threshold and the
+    /// transaction stays below 90% of the compute unit limit. Checked on the failed
+    /// transactions as well, which must not run out of compute units.
+    ComputeUnits,
+}
+
+impl BuiltinInvariant {
+    pub const ALL: [BuiltinInvariant; 5] = [
+        BuiltinInvariant::LamportConservation,
+        BuiltinInvariant::RentExemption,
+        BuiltinInvariant::ClosedAccounts,
+        BuiltinInvariant::ReadonlyAccounts,
+        BuiltinInvariant::ComputeUnits,
+    ];
+}
+
+impl FromStr for BuiltinInvariant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lamport_conservation" => Ok(BuiltinInvariant::LamportConservation),
+            "rent_exemption" => Ok(BuiltinInvariant::RentExemption),
+            "closed_accounts" => Ok(BuiltinInvariant::ClosedAccounts),
+            "readonly_accounts" => Ok(BuiltinInvariant::ReadonlyAccounts),
+            "compute_units" => Ok(BuiltinInvariant::ComputeUnits),
+            _ => Err(format!(
+                "unknown built-in invariant `{}`, expected one of `lamport_conservation`, `rent_exemption`, `closed_accounts`, `readonly_accounts` or `compute_units`",
+                s
+            )),
+        }
+    }
+}
+
+/// Enables the built-in invariants in addition to the already enabled ones.
+pub fn enable_builtin_invariants(invariants: &[BuiltinInvariant]) {
+    BUILTIN_INVARIANTS.with(|enabled| {
+        let mut enabled = enabled.borrow_mut();
+        for invariant in invariants {
+            if !enabled.contains(invariant) {
+                enabled.push(*invariant);
+            }
+        }
+    });
+}
+
+/// Sets the compute units a single instruction is allowed to consume,
+/// checked if the compute units invariant is enabled.
+/// Only the first call has an effect, later calls are ignored.
+pub fn set_compute_unit_threshold(threshold: Option<u64>) {
+    let _ = COMPUTE_UNIT_THRESHOLD.set(threshold);
+}
+
+/// State of the accounts referenced by the transaction before its execution.
+pub(crate) struct AccountsBefore {
+    accounts: Vec<(Pubkey, AccountSharedData, bool)>,
+    fee: u64,
+}
+
+impl AccountsBefore {
+    /// Captures the accounts if any built-in invariant is enabled.
+    pub(crate) fn capture(
+        client: &mut impl FuzzClient,
+        instructions: &[Instruction],
+    ) -> Option<Self> {
+        if BUILTIN_INVARIANTS.with(|enabled| enabled.borrow().is_empty()) {
+            return None;
+        }
+
+        let payer = client.payer().pubkey();
+
+        // the payer is writable and signs every transaction
+        let mut keys: Vec<(Pubkey, bool, bool)> = vec![(payer, true, true)];
+        for meta in instructions.iter().flat_map(|ix| ix.accounts.iter()) {
+            match keys
+                .iter_mut()
+                .find(|(address, _, _)| *address == meta.pubkey)
+            {
+                Some((_, is_writable, is_signer)) => {
+                    *is_writable |= meta.is_writable;
+                    *is_signer |= meta.is_signer;
+                }
+                None => keys.push((meta.pubkey, meta.is_writable, meta.is_signer)),
+            }
+        }
+
+        let signatures = keys.iter().filter(|(_, _, is_signer)| *is_signer).count() as u64;
+        let accounts = keys
+            .into_iter()
+            .map(|(address, is_writable, _)| (address, client.get_account(&address), is_writable))
+            .collect();
+
+        Some(Self {
+            accounts,
+            fee: FeeStructure::default().lamports_per_signature * signatures,
+        })
+    }
+
+    /// Checks the enabled built-in invariants against the current state of the accounts.
+    pub(crate) fn check(&self, client: &mut impl FuzzClient) -> Result<(), FuzzingError> {
+        let invariants = BUILTIN_INVARIANTS.with(|enabled| enabled.borrow().clone());
+        let rent = client.get_sysvar::<Rent>();
+
+        let accounts: Vec<_> = self
+            .accounts
+            .iter()
+            .map(|(address, before, is_writable)| {
+                (address, before, client.get_account(address), *is_writable)
+            })
+            .collect();
+
+        for invariant in invariants {
+            check_accounts(invariant, &accounts, self.fee, &rent)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Account referenced by the transaction, its state before and after the transaction
+/// and whether it is writable.
+type AccountChange<'a> = (&'a Pubkey, &'a AccountSharedData, AccountSharedData, bool);
+
+/// Checks the invariant on the accounts referenced by the transaction which paid the `fee`.
+fn check_accounts(
+    invariant: BuiltinInvariant,
+    accounts: &[AccountChange],
+    fee: u64,
+    rent: &Rent,
+) -> Result<(), FuzzingError> {
+    match invariant {
+        BuiltinInvariant::LamportConservation => {
+            let before: u128 = accounts
+                .iter()
+                .map(|(_, before, _, _)| before.lamports() as u128)
+                .sum();
+            let after: u128 = accounts
+                .iter()
+                .map(|(_, _, after, _)| after.lamports() as u128)
+                .sum();
+            if after > before || before - after > fee as u128 {
+                return Err(FuzzingError::with_message(&format!(
+                    "Lamports are not conserved: {} lamports before and {} lamports after the transaction with the fee of {} lamports",
+                    before, after, fee
+                )));
+            }
+        }
+        BuiltinInvariant::RentExemption => {
+            for (address, before, after, is_writable) in accounts {
+                let program_owned = *after.owner() != system_program::ID
+                    && !after.executable()
+                    && after.lamports() > 0;
+                let was_exempt = before.lamports() == 0
+                    || rent.is_exempt(before.lamports(), before.data().len());
+                if *is_writable
+                    && program_owned
+                    && was_exempt
+                    && !rent.is_exempt(after.lamports(), after.data().len())
+                {
+                    return Err(FuzzingError::with_message(&format!(
+                        "Account {} is not rent exempt: {} lamports for {} bytes of data",
+                        address,
+                        after.lamports(),
+                        after.data().len()
+                    )));
+                }
+            }
+        }
+        BuiltinInvariant::ClosedAccounts => {
+            for (address, before, after, _) in accounts {
+                let closed = before.lamports() > 0 && after.lamports() == 0;
+                if closed
+                    && (*after.owner() != system_program::ID
+                        || after.data().iter().any(|byte| *byte != 0))
+                {
+                    return Err(FuzzingError::with_message(&format!(
+                        "Closed account {} is not zeroed and reassigned to the system program, its owner is {}",
+                        address,
+                        after.owner()
+                    )));
+                }
+            }
+        }
+        BuiltinInvariant::ReadonlyAccounts => {
+            for (address, before, after, is_writable) in accounts {
+                if !*is_writable
+                    && (before.lamports() != after.lamports() || before.data() != after.data())
+                {
+                    return Err(FuzzingError::with_message(&format!(
+                        "Read-only account {} was modified by the transaction",
+                        address
+                    )));
+                }
+            }
+        }
+        BuiltinInvariant::ComputeUnits => check_compute_units()?,
+    }
+    Ok(())
+}
+
+/// Checks the compute units consumed by the last executed transaction.
+fn check_compute_units() -> Result<(), FuzzingError> {
+    let result = get_transaction_result();
+
+    if let Some(threshold) = COMPUTE_UNIT_THRESHOLD.get().copied().flatten() {
+        for (index, compute_units) in result.instruction_compute_units().iter().enumerate() {
+            match compute_units {
+                Some(compute_units) if *compute_units > threshold => {
+                    return Err(FuzzingError::with_message(&format!(
+                        "Instruction {} consumed {} compute units, above the threshold of {} compute units",
+                        index, compute_units, threshold
+                    )));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let limit = MAX_TRANSACTION_COMPUTE_UNITS * COMPUTE_UNIT_LIMIT_PERCENTAGE / 100;
+    if result.compute_units_consumed() > limit {
+        return Err(FuzzingError::with_message(&format!(
+            "Transaction consumed {} compute units, close to the limit of {} compute units",
+            result.compute_units_consumed(),
+            MAX_TRANSACTION_COMPUTE_UNITS
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the compute units consumed by the last failed transaction if the compute units
+/// invariant is enabled, the transaction which ran out of compute units fails the check.
+pub(crate) fn check_failed_transaction_compute_units(
+    error: &TransactionError,
+) -> Result<(), FuzzingError> {
+    let enabled = BUILTIN_INVARIANTS
+        .with(|enabled| enabled.borrow().contains(&BuiltinInvariant::ComputeUnits));
+    if !enabled {
+        return Ok(());
+    }
+
+    check_compute_budget(error, get_transaction_result().logs())?;
+    check_compute_units()
+}
+
+/// Fails if the transaction ran out of compute units, either within the program
+/// or within a syscall.
+fn check_compute_budget(error: &TransactionError, logs: &[String]) -> Result<(), FuzzingError> {
+    let exceeded_budget = matches!(
+        error,
+        TransactionError::InstructionError(_, InstructionError::ComputationalBudgetExceeded)
+    ) || logs
+        .iter()
+        .any(|log| log.contains(EXCEEDED_COMPUTE_UNITS_LOG));
+
+    if exceeded_budget {
+        return Err(FuzzingError::with_message(&format!(
+            "Transaction ran out of compute units: {}",
+            error
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the built-in invariants if the accounts were captured before the transaction.
+pub(crate) fn check_builtin_invariants(
+    accounts_before: &Option<AccountsBefore>,
+    client: &mut impl FuzzClient,
+) -> Result<(), FuzzingError> {
+    match accounts_before {
+        Some(accounts_before) => accounts_before.check(client),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEE: u64 = 5000;
+
+    fn account(lamports: u64, data: &[u8], owner: &Pubkey) -> AccountSharedData {
+        let mut account = AccountSharedData::new(lamports, data.len(), owner);
+        account.set_data_from_slice(data);
+        account
+    }
+
+    /// Checks the invariant on the accounts given as (before, after, is_writable).
+    fn check(
+        invariant: BuiltinInvariant,
+        accounts: &[(AccountSharedData, AccountSharedData, bool)],
+    ) -> Result<(), FuzzingError> {
+        let addresses: Vec<Pubkey> = accounts.iter().map(|_| Pubkey::new_unique()).collect();
+        let changes: Vec<AccountChange> = addresses
+            .iter()
+            .zip(accounts)
+            .map(|(address, (before, after, is_writable))| {
+                (address, before, after.clone(), *is_writable)
+            })
+            .collect();
+        check_accounts(invariant, &changes, FEE, &Rent::default())
+    }
+
+    #[test]
+    fn test_lamport_conservation_with_fee() {
+        let program = Pubkey::new_unique();
+        let payer_before = account(1_000_000, &[], &system_program::ID);
+        let payer_after = account(1_000_000 - FEE - 100, &[], &system_program::ID);
+
+        // the payer pays the fee and transfers 100 lamports
+        let accounts = [
+            (payer_before.clone(), payer_after.clone(), true),
+            (
+                account(1000, &[1], &program),
+                account(1100, &[1], &program),
+                true,
+            ),
+        ];
+        assert!(check(BuiltinInvariant::LamportConservation, &accounts).is_ok());
+
+        // the fee is only the upper bound of the lost lamports
+        let accounts = [(payer_before.clone(), payer_before.clone(), true)];
+        assert!(check(BuiltinInvariant::LamportConservation, &accounts).is_ok());
+
+        // more lamports than the fee are lost
+        let accounts = [(
+            payer_before.clone(),
+            account(1_000_000 - FEE - 1, &[], &system_program::ID),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::LamportConservation, &accounts).is_err());
+
+        // lamports are created
+        let accounts = [
+            (payer_before, payer_after, true),
+            (
+                account(1000, &[1], &program),
+                account(1000 + FEE + 101, &[1], &program),
+                true,
+            ),
+        ];
+        assert!(check(BuiltinInvariant::LamportConservation, &accounts).is_err());
+    }
+
+    #[test]
+    fn test_rent_exemption_transition() {
+        let program = Pubkey::new_unique();
+        let rent = Rent::default();
+        let exempt = rent.minimum_balance(10);
+        let data = [0; 10];
+
+        // the rent exempt account drops below the rent exemption
+        let accounts = [(
+            account(exempt, &data, &program),
+            account(exempt - 1, &data, &program),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::RentExemption, &accounts).is_err());
+
+        // the account grows without the lamports for the new data
+        let accounts = [(
+            account(exempt, &data, &program),
+            account(exempt, &[0; 20], &program),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::RentExemption, &accounts).is_err());
+
+        // the account created by the transaction is not rent exempt
+        let accounts = [(
+            AccountSharedData::default(),
+            account(exempt - 1, &data, &program),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::RentExemption, &accounts).is_err());
+
+        // the account which was not rent exempt before is not flagged
+        let accounts = [(
+            account(exempt - 2, &data, &program),
+            account(exempt - 1, &data, &program),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::RentExemption, &accounts).is_ok());
+
+        // the accounts owned by the system program and the closed accounts are not flagged
+        let accounts = [
+            (
+                account(exempt, &data, &system_program::ID),
+                account(exempt - 1, &data, &system_program::ID),
+                true,
+            ),
+            (
+                account(exempt, &data, &program),
+                account(0, &data, &program),
+                true,
+            ),
+        ];
+        assert!(check(BuiltinInvariant::RentExemption, &accounts).is_ok());
+    }
+
+    #[test]
+    fn test_closed_accounts() {
+        let program = Pubkey::new_unique();
+
+        // the closed account is zeroed and reassigned to the system program
+        let accounts = [(
+            account(1000, &[1, 2, 3], &program),
+            account(0, &[0, 0, 0], &system_program::ID),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::ClosedAccounts, &accounts).is_ok());
+
+        // the closed account is still owned by the program
+        let accounts = [(
+            account(1000, &[1, 2, 3], &program),
+            account(0, &[], &program),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::ClosedAccounts, &accounts).is_err());
+
+        // the closed account keeps its data
+        let accounts = [(
+            account(1000, &[1, 2, 3], &program),
+            account(0, &[1, 2, 3], &system_program::ID),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::ClosedAccounts, &accounts).is_err());
+
+        // the account which did not exist before the transaction is not closed
+        let accounts = [(
+            AccountSharedData::default(),
+            account(0, &[1], &program),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::ClosedAccounts, &accounts).is_ok());
+    }
+
+    #[test]
+    fn test_compute_budget_exceeded() {
+        let program = Pubkey::new_unique();
+        let failed = TransactionError::InstructionError(0, InstructionError::Custom(1));
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            format!("Program {} failed: custom program error: 0x1", program),
+        ];
+        assert!(check_compute_budget(&failed, &logs).is_ok());
+
+        // the syscall ran out of compute units
+        let exceeded =
+            TransactionError::InstructionError(0, InstructionError::ComputationalBudgetExceeded);
+        assert!(check_compute_budget(&exceeded, &[]).is_err());
+
+        // the program ran out of compute units
+        let failed_to_complete =
+            TransactionError::InstructionError(0, InstructionError::ProgramFailedToComplete);
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            format!(
+                "Program {} consumed 200000 of 200000 compute units",
+                program
+            ),
+            format!(
+                "Program {} failed: exceeded CUs meter at BPF instruction #1234",
+                program
+            ),
+        ];
+        assert!(check_compute_budget(&failed_to_complete, &logs).is_err());
+    }
+
+    #[test]
+    fn test_readonly_accounts() {
+        let program = Pubkey::new_unique();
+
+        // the writable account is modified
+        let accounts = [(
+            account(1000, &[1], &program),
+            account(900, &[2], &program),
+            true,
+        )];
+        assert!(check(BuiltinInvariant::ReadonlyAccounts, &accounts).is_ok());
+
+        // the read-only account is unchanged
+        let accounts = [(
+            account(1000, &[1], &program),
+            account(1000, &[1], &program),
+            false,
+        )];
+        assert!(check(BuiltinInvariant::ReadonlyAccounts, &accounts).is_ok());
+
+        // the lamports of the read-only account change
+        let accounts = [(
+            account(1000, &[1], &program),
+            account(999, &[1], &program),
+            false,
+        )];
+        assert!(check(BuiltinInvariant::ReadonlyAccounts, &accounts).is_err());
+
+        // the data of the read-only account change
+        let accounts = [(
+            account(1000, &[1], &program),
+            account(1000, &[2], &program),
+            false,
+        )];
+        assert!(check(BuiltinInvariant::ReadonlyAccounts, &accounts).is_err());
+    }
+}
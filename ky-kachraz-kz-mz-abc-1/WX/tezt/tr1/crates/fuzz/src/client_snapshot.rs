@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+use crate::traits::FuzzClient;
+
+thread_local! {
+    /// State of every client, keyed by the client id. The client is a foreign
+    /// type, so its state cannot be stored within it.
+    static CLIENT_STATES: RefCell<HashMap<Pubkey, ClientState>> = RefCell::new(HashMap::new());
+}
+
+/// Accounts loaded from the `Trident.toml` and accounts written by a single client.
+#[derive(Default)]
+struct ClientState {
+    /// Addresses of the accounts loaded from the `Trident.toml`.
+    permanent_addresses: HashSet<Pubkey>,
+    /// Accounts loaded from the `Trident.toml` modified by the transactions, kept across
+    /// the fuzzing iterations.
+    permanent_accounts: HashMap<Pubkey, AccountSharedData>,
+    /// Accounts written by the client since the temporary accounts were cleared.
+    written_accounts: HashSet<Pubkey>,
+}
+
+/// State of the client captured by `FuzzClient::snapshot`, the accounts written by the
+/// client and the sysvars. It can be restored only by the client which has taken it.
+#[derive(Clone)]
+pub struct ClientSnapshot {
+    pub(crate) client: Pubkey,
+    pub(crate) accounts: Vec<(Pubkey, AccountSharedData)>,
+    pub(crate) sysvars: Vec<(Pubkey, AccountSharedData)>,
+}
+
+impl ClientSnapshot {
+    /// Whether the snapshot was taken by the client, the clients are identified by their payers.
+    pub fn is_taken_by(&self, client: &impl FuzzClient) -> bool {
+        self.client == client.payer().pubkey()
+    }
+}
+
+/// Remembers the addresses of the accounts the client has loaded from the `Trident.toml`.
+pub(crate) fn register_permanent_accounts(client: &Pubkey, addresses: &[Pubkey]) {
+    CLIENT_STATES.with(|states| {
+        states
+            .borrow_mut()
+            .entry(*client)
+            .or_default()
+            .permanent_addresses
+            .extend(addresses)
+    });
+}
+
+/// Keeps the account modified by the transaction if it was loaded from the `Trident.toml`,
+/// returns false for the other accounts.
+pub(crate) fn settle_permanent_account(
+    client: &Pubkey,
+    address: &Pubkey,
+    account: &AccountSharedData,
+) -> bool {
+    CLIENT_STATES.with(|states| {
+        let mut states = states.borrow_mut();
+        let Some(state) = states.get_mut(client) else {
+            return false;
+        };
+        if !state.permanent_addresses.contains(address) {
+            return false;
+        }
+        state.permanent_accounts.insert(*address, account.clone());
+        true
+    })
+}
+
+/// Returns the accounts loaded from the `Trident.toml` modified by the transactions.
+pub(crate) fn get_permanent_accounts(client: &Pubkey) -> Vec<(Pubkey, AccountSharedData)> {
+    CLIENT_STATES.with(|states| {
+        states
+            .borrow()
+            .get(client)
+            .map(|state| {
+                state
+                    .permanent_accounts
+                    .iter()
+                    .map(|(address, account)| (*address, account.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// Remembers the account written by the client, so it is captured by the next snapshot.
+pub(crate) fn record_written_account(client: &Pubkey, address: &Pubkey) {
+    CLIENT_STATES.with(|states| {
+        states
+            .borrow_mut()
+            .entry(*client)
+            .or_default()
+            .written_accounts
+            .insert(*address)
+    });
+}
+
+/// Returns the accounts written by the client since the temporary accounts were cleared.
+pub(crate) fn get_written_accounts(client: &Pubkey) -> Vec<Pubkey> {
+    CLIENT_STATES.with(|states| {
+        states
+            .borrow()
+            .get(client)
+            .map(|state| state.written_accounts.iter().copied().collect())
+            .unwrap_or_default()
+    })
+}
+
+/// Forgets the accounts written by the client once its temporary accounts are cleared.
+pub(crate) fn clear_written_accounts(client: &Pubkey) {
+    CLIENT_STATES.with(|states| {
+        if let Some(state) = states.borrow_mut().get_mut(client) {
+            state.written_accounts.clear();
+        }
+    });
+}
@@ -0,0 +1,27 @@
+//! Replay of the saved corpus by the fuzz test built with the source-based coverage.
+
+use std::io::Write;
+
+/// Printed to stdout once an input is replayed, so `trident fuzz coverage` can tell
+/// which input aborted or hung the replay.
+pub const REPLAYED_INPUT_MARKER: &str = "trident:replayed";
+
+/// Executes the closure for every input whose path is read from stdin. The inputs
+/// which panic are replayed as well, the coverage is written once stdin is closed.
+pub fn fuzz<F>(mut closure: F)
+where
+    F: FnMut(&[u8]),
+{
+    std::panic::set_hook(Box::new(|_| {}));
+
+    for input in std::io::stdin().lines().map_while(Result::ok) {
+        if let Ok(fuzzer_data) = std::fs::read(input.trim()) {
+            let _ =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| closure(&fuzzer_data)));
+        }
+
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}", REPLAYED_INPUT_MARKER);
+        let _ = stdout.flush();
+    }
+}
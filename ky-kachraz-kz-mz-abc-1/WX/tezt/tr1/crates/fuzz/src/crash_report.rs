@@ -0,0 +1,102 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+/// Environment variable containing the path the crash report is written to.
+pub const TRIDENT_CRASH_REPORT: &str = "TRIDENT_CRASH_REPORT";
+
+thread_local! {
+    static CURRENT_TRANSACTION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Describes the first panic observed while replaying a crash file.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CrashReport {
+    /// Name of the transaction which was executed when the panic occurred.
+    pub transaction: Option<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+impl CrashReport {
+    /// Installs a panic hook which writes the report of the first panic to the file
+    /// specified by the `TRIDENT_CRASH_REPORT` environment variable.
+    ///
+    /// Panics inside of the programs are caught by the SVM, so only the first panic
+    /// is reported, as it is the one which aborts the process during fuzzing.
+    /// Does nothing if the environment variable is not set.
+    pub fn install_panic_hook() {
+        let report_path = match std::env::var(TRIDENT_CRASH_REPORT) {
+            Ok(path) => path,
+            Err(_) => return,
+        };
+
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            if !REPORTED.swap(true, Ordering::SeqCst) {
+                let report = CrashReport {
+                    transaction: CURRENT_TRANSACTION.with(|current| current.borrow().clone()),
+                    message: panic_message(panic_info.payload()),
+                    location: panic_info.location().map(|location| location.to_string()),
+                    backtrace: Backtrace::force_capture().to_string(),
+                };
+
+                if let Ok(serialized) = serde_json::to_string(&report) {
+                    let _ = std::fs::write(&report_path, serialized);
+                }
+            }
+            default_hook(panic_info);
+        }));
+    }
+
+    /// Runs the closure and panics if any panic occurred within it, including the panics
+    /// of the programs which are caught by the SVM and would abort the process during fuzzing.
+    pub fn assert_no_panic<T>(f: impl FnOnce() -> T) -> T {
+        PANIC_HOOK.call_once(|| {
+            let default_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |panic_info| {
+                PANIC_MESSAGE.with(|message| {
+                    message
+                        .borrow_mut()
+                        .get_or_insert_with(|| panic_message(panic_info.payload()));
+                });
+                default_hook(panic_info);
+            }));
+        });
+
+        PANIC_MESSAGE.with(|message| *message.borrow_mut() = None);
+        let result = f();
+
+        if let Some(message) = PANIC_MESSAGE.with(|message| message.borrow_mut().take()) {
+            panic!("Panic occurred during the execution: {}", message);
+        }
+        result
+    }
+
+    /// Records the name of the transaction which is about to be executed.
+    pub fn set_current_transaction(transaction: String) {
+        CURRENT_TRANSACTION.with(|current| *current.borrow_mut() = Some(transaction));
+    }
+}
+
+/// Extracts the message from the payload of a panic.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "Box<dyn Any>".to_string(),
+        },
+    }
+}
@@ -0,0 +1,174 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::FuzzingError;
+use crate::fuzz_stats::FuzzingStatistics;
+use crate::replay_log::record_invariant_failure;
+use crate::traits::FuzzClient;
+
+type CheckInvariants = dyn Fn(&mut dyn Any, &dyn Any) -> Result<(), FuzzingError>;
+
+thread_local! {
+    /// The `#[invariant]` functions of the flow executor.
+    static FLOW_INVARIANTS: RefCell<Option<Rc<CheckInvariants>>> = const { RefCell::new(None) };
+    /// Name of the successfully executed transaction the invariants were not checked after yet.
+    static PENDING_CHECK: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Sets the `#[invariant]` functions checked after every successfully executed transaction,
+/// the client is used only to infer its type. Forgets the pending check of the previous iteration.
+pub fn set_flow_invariants<C, A, F>(_client: &C, check: F)
+where
+    C: FuzzClient,
+    A: 'static,
+    F: Fn(&mut C, &A) -> Result<(), FuzzingError> + 'static,
+{
+    let check: Rc<CheckInvariants> = Rc::new(move |client, accounts| {
+        let client = client
+            .downcast_mut::<C>()
+            .expect("the invariants are checked with the client they were set for");
+        let accounts = accounts
+            .downcast_ref::<A>()
+            .expect("the invariants are checked on the accounts they were set for");
+        check(client, accounts)
+    });
+    FLOW_INVARIANTS.with(|flow_invariants| *flow_invariants.borrow_mut() = Some(check));
+    PENDING_CHECK.with(|pending| pending.borrow_mut().take());
+}
+
+/// Schedules the check of the `#[invariant]` functions after the successfully executed
+/// transaction. The accounts are borrowed by the flow while the transaction is executed,
+/// so the invariants are checked once the accounts are available again.
+pub(crate) fn schedule_flow_invariants(transaction_name: String) {
+    let enabled = FLOW_INVARIANTS.with(|flow_invariants| flow_invariants.borrow().is_some());
+    if enabled {
+        PENDING_CHECK.with(|pending| *pending.borrow_mut() = Some(transaction_name));
+    }
+}
+
+/// Checks the `#[invariant]` functions on the live accounts if a transaction was executed
+/// since the last check, called when the next transaction is built and when the flows end.
+/// Panics if any of the invariants does not pass.
+pub fn check_flow_invariants<C: FuzzClient, A: 'static>(client: &mut C, accounts: &A) {
+    let Some(transaction_name) = PENDING_CHECK.with(|pending| pending.borrow_mut().take()) else {
+        return;
+    };
+    // the invariants may build transactions themselves, so the cell is not borrowed meanwhile
+    let Some(check) = FLOW_INVARIANTS.with(|flow_invariants| flow_invariants.borrow().clone())
+    else {
+        return;
+    };
+
+    if let Err(error) = check(client, accounts) {
+        record_invariant_failure(&error.to_string());
+
+        if std::env::var("FUZZING_METRICS").is_ok() {
+            let mut stats_logger = FuzzingStatistics::new();
+            stats_logger.increase_failed_check(transaction_name.clone());
+            stats_logger.output_serialized();
+        }
+
+        eprintln!(
+            "\x1b[31mCRASH DETECTED!\x1b[0m Invariant check after the {} transaction did not pass!",
+            transaction_name
+        );
+        panic!("{}", error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    use trident_svm::trident_svm::TridentSVM;
+
+    #[derive(Default)]
+    struct FuzzAccounts {
+        checks: Rc<Cell<u32>>,
+    }
+
+    fn count_checks(client: &TridentSVM) {
+        set_flow_invariants(
+            client,
+            |_client: &mut TridentSVM, accounts: &FuzzAccounts| {
+                accounts.checks.set(accounts.checks.get() + 1);
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn test_invariants_checked_once_after_transaction() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let accounts = FuzzAccounts::default();
+        count_checks(&client);
+
+        check_flow_invariants(&mut client, &accounts);
+        assert_eq!(accounts.checks.get(), 0);
+
+        schedule_flow_invariants("Deposit".to_string());
+        check_flow_invariants(&mut client, &accounts);
+        check_flow_invariants(&mut client, &accounts);
+        assert_eq!(accounts.checks.get(), 1);
+    }
+
+    #[test]
+    fn test_pending_check_is_forgotten_in_next_iteration() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let accounts = FuzzAccounts::default();
+        count_checks(&client);
+
+        schedule_flow_invariants("Deposit".to_string());
+        count_checks(&client);
+        check_flow_invariants(&mut client, &accounts);
+        assert_eq!(accounts.checks.get(), 0);
+    }
+
+    #[test]
+    fn test_invariants_may_execute_transactions() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let accounts = FuzzAccounts::default();
+        set_flow_invariants(
+            &client,
+            |client: &mut TridentSVM, accounts: &FuzzAccounts| {
+                accounts.checks.set(accounts.checks.get() + 1);
+                // a transaction built and executed by the invariant
+                check_flow_invariants(client, accounts);
+                schedule_flow_invariants("Withdraw".to_string());
+                Ok(())
+            },
+        );
+
+        schedule_flow_invariants("Deposit".to_string());
+        check_flow_invariants(&mut client, &accounts);
+        assert_eq!(accounts.checks.get(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invariant violated")]
+    fn test_failed_invariant_panics() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        set_flow_invariants(
+            &client,
+            |_client: &mut TridentSVM, _accounts: &FuzzAccounts| {
+                Err(FuzzingError::with_message("Invariant violated"))
+            },
+        );
+
+        schedule_flow_invariants("Deposit".to_string());
+        check_flow_invariants(&mut client, &FuzzAccounts::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "the invariants are checked on the accounts they were set for")]
+    fn test_invariants_on_other_accounts_panic() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        count_checks(&client);
+
+        schedule_flow_invariants("Deposit".to_string());
+        check_flow_invariants(&mut client, &0u8);
+    }
+}
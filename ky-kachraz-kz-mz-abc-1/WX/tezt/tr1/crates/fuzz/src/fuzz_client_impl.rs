@@ -1,11 +1,27 @@
+use solana_sdk::account::from_account;
 use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::ReadableAccount;
 use solana_sdk::clock::Clock;
+use solana_sdk::epoch_rewards::EpochRewards;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::hash::Hash;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::sanitize::Sanitize;
+use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
-use solana_sdk::sysvar::Sysvar;
+use solana_sdk::slot_hashes::SlotHashes;
+use solana_sdk::slot_history::SlotHistory;
+use solana_sdk::stake_history::StakeHistory;
+#[allow(deprecated)]
+use solana_sdk::sysvar::fees::Fees;
+use solana_sdk::sysvar::last_restart_slot::LastRestartSlot;
+#[allow(deprecated)]
+use solana_sdk::sysvar::recent_blockhashes::RecentBlockhashes;
+use solana_sdk::sysvar::{Sysvar, SysvarId};
 
+use solana_svm::transaction_results::TransactionExecutionResult;
 use trident_config::TridentConfig;
 
 use trident_svm::trident_svm::TridentSVM;
@@ -13,14 +29,104 @@ use trident_svm::utils::ProgramEntrypoint;
 use trident_svm::utils::SBFTarget;
 use trident_svm::utils::TridentAccountSharedData;
 
+use crate::accounts_storage::malicious_account::clear_malicious_accounts;
+use crate::accounts_storage::{
+    clear_signers, get_signers, register_client_signer, set_address_seed,
+};
+use crate::authorization_attacks::set_authorization_attacks;
+use crate::builtin_invariants::{
+    enable_builtin_invariants, set_compute_unit_threshold, BuiltinInvariant,
+};
+use crate::client_snapshot::{
+    clear_written_accounts, get_permanent_accounts, get_written_accounts, record_written_account,
+    register_permanent_accounts, settle_permanent_account, ClientSnapshot,
+};
 use crate::traits::FuzzClient;
+use crate::transaction_result::TransactionResult;
+use crate::transaction_sequence::{
+    clear_executed_transactions, set_allow_duplicate_txs, set_transactions_bounds,
+};
+use crate::transaction_weights::set_transaction_weights;
 use solana_sdk::transaction::TransactionError;
 
+/// Identifies the client within the snapshot state, the payer keypair is generated
+/// for every client.
+fn client_id(client: &TridentSVM) -> Pubkey {
+    client.get_payer().pubkey()
+}
+
+/// Stores the accounts modified by the successful transaction. Accounts loaded from the
+/// `Trident.toml` keep the modification across the fuzzing iterations, the other accounts
+/// are temporary.
+fn settle_accounts(client: &mut TridentSVM, accounts: &[(Pubkey, AccountSharedData)]) {
+    let client_id = client_id(client);
+    for (address, account) in accounts {
+        if !account.executable() && account.owner() != &solana_sdk::sysvar::id() {
+            client.add_temp_account(address, account);
+            if !settle_permanent_account(&client_id, address, account) {
+                record_written_account(&client_id, address);
+            }
+        }
+    }
+}
+
+/// Drops the temporary accounts, the modified accounts loaded from the `Trident.toml`
+/// are stored again as the permanent accounts of the SVM cannot be updated by the client.
+fn reset_temp_accounts(client: &mut TridentSVM) {
+    let client_id = client_id(client);
+    trident_svm::trident_svm::TridentSVM::clear_accounts(client);
+    clear_written_accounts(&client_id);
+    for (address, account) in get_permanent_accounts(&client_id) {
+        client.add_temp_account(&address, &account);
+    }
+}
+
+/// Sets the sysvar from its account captured by the snapshot.
+type SetSysvar = fn(&mut TridentSVM, &AccountSharedData);
+
+/// Sysvars the client can set, captured by the snapshot along with the written accounts.
+#[allow(deprecated)]
+fn snapshot_sysvars() -> [(Pubkey, SetSysvar); 10] {
+    [
+        (Clock::id(), set_sysvar::<Clock>),
+        (EpochRewards::id(), set_sysvar::<EpochRewards>),
+        (EpochSchedule::id(), set_sysvar::<EpochSchedule>),
+        (Fees::id(), set_sysvar::<Fees>),
+        (LastRestartSlot::id(), set_sysvar::<LastRestartSlot>),
+        (RecentBlockhashes::id(), set_sysvar::<RecentBlockhashes>),
+        (Rent::id(), set_sysvar::<Rent>),
+        (SlotHashes::id(), set_sysvar::<SlotHashes>),
+        (SlotHistory::id(), set_sysvar::<SlotHistory>),
+        (StakeHistory::id(), set_sysvar::<StakeHistory>),
+    ]
+}
+
+fn set_sysvar<S: Sysvar + SysvarId>(client: &mut TridentSVM, account: &AccountSharedData) {
+    let sysvar: S =
+        from_account(account).expect("The sysvar account captured by the snapshot is valid");
+    client.set_sysvar(&sysvar);
+}
+
 impl FuzzClient for TridentSVM {
     fn deploy_native_program(&mut self, program: ProgramEntrypoint) {
         trident_svm::trident_svm::TridentSVM::deploy_native_program(self, program);
     }
     fn new_client(programs: &[ProgramEntrypoint], config: &TridentConfig) -> Self {
+        set_address_seed(&config.get_address_seed());
+        set_authorization_attacks(config.get_authorization_attacks());
+        set_transaction_weights(config.get_transaction_weights());
+        set_allow_duplicate_txs(config.get_allow_duplicate_txs());
+        set_transactions_bounds(config.get_min_transactions(), config.get_max_transactions());
+
+        let builtin_invariants = config
+            .get_builtin_invariants()
+            .iter()
+            .map(|name| name.parse::<BuiltinInvariant>())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| panic!("{}", e));
+        enable_builtin_invariants(&builtin_invariants);
+        set_compute_unit_threshold(config.get_compute_unit_threshold());
+
         let sbf_programs =
             config
                 .programs()
@@ -49,7 +155,14 @@ impl FuzzClient for TridentSVM {
                     permanent_accounts
                 });
 
-        TridentSVM::new_with_syscalls(programs, &sbf_programs, &permanent_accounts)
+        let client = TridentSVM::new_with_syscalls(programs, &sbf_programs, &permanent_accounts);
+        let permanent_addresses = config
+            .accounts()
+            .iter()
+            .map(|config_account| config_account.pubkey)
+            .collect::<Vec<_>>();
+        register_permanent_accounts(&client_id(&client), &permanent_addresses);
+        client
     }
     fn warp_to_epoch(&mut self, warp_epoch: u64) {
         let mut clock = self.get_sysvar::<Clock>();
@@ -80,12 +193,17 @@ impl FuzzClient for TridentSVM {
 
     fn set_account_custom(&mut self, address: &Pubkey, account: &AccountSharedData) {
         self.add_temp_account(address, account);
+        record_written_account(&client_id(self), address);
     }
 
     fn payer(&self) -> solana_sdk::signature::Keypair {
         self.get_payer()
     }
 
+    fn add_signer(&mut self, keypair: &Keypair) {
+        register_client_signer(keypair);
+    }
+
     fn get_account(&mut self, key: &Pubkey) -> AccountSharedData {
         trident_svm::trident_svm::TridentSVM::get_account(self, key).unwrap_or_default()
     }
@@ -94,21 +212,80 @@ impl FuzzClient for TridentSVM {
         panic!("Not yet implemented for TridentSVM");
     }
 
-    fn process_instructions(
-        &mut self,
-        instructions: &[Instruction],
-    ) -> Result<(), TransactionError> {
+    fn process_instructions(&mut self, instructions: &[Instruction]) -> TransactionResult {
         // there should be at least 1 RW fee-payer account.
         // But we do not pay for TX currently so has to be manually updated
         // tx.message.header.num_required_signatures = 1;
         // tx.message.header.num_readonly_signed_accounts = 0;
-        let tx = solana_sdk::transaction::Transaction::new_with_payer(
+        let payer = self.payer();
+        let mut tx = solana_sdk::transaction::Transaction::new_with_payer(
             instructions,
-            Some(&self.payer().pubkey()),
+            Some(&payer.pubkey()),
         );
 
-        self.process_transaction_with_settle(tx)?;
-        Ok(())
+        // sign with the payer and with the keypairs of the required signers created by the
+        // storages or added to the client, the signers without a keypair cannot sign the transaction
+        let mut signers = vec![payer];
+        signers.extend(get_signers(tx.message.signer_keys().into_iter().skip(1)));
+        let signers: Vec<&Keypair> = signers.iter().collect();
+        if tx.try_partial_sign(&signers, Hash::default()).is_err() {
+            return TransactionResult::not_executed(TransactionError::SignatureFailure);
+        }
+
+        // the SVM does not verify the signatures
+        if let Err(e) = tx.verify() {
+            return TransactionResult::not_executed(e);
+        }
+        if tx.sanitize().is_err() {
+            return TransactionResult::not_executed(TransactionError::SanitizeFailure);
+        }
+
+        if std::env::var("TRIDENT_LOG").is_ok() {
+            solana_logger::setup_with_default(
+                "solana_rbpf::vm=debug,\
+                    solana_runtime::message_processor=debug,\
+                    solana_runtime::system_instruction_processor=trace",
+            );
+        } else {
+            solana_logger::setup_with_default("off");
+        }
+
+        // the transaction is processed without settling the accounts, so the logs,
+        // return data, inner instructions and compute units are available
+        let output = self.process_transaction(tx);
+
+        let (Some(execution_result), Some(loaded_transaction)) = (
+            output.execution_results.first(),
+            output.loaded_transactions.first(),
+        ) else {
+            return TransactionResult::not_executed(TransactionError::ProgramCacheHitMaxLimit);
+        };
+
+        match execution_result {
+            TransactionExecutionResult::Executed { details, .. } => {
+                let result = details
+                    .status
+                    .clone()
+                    .and_then(|_| match loaded_transaction {
+                        Ok(loaded_transaction) => {
+                            settle_accounts(self, &loaded_transaction.accounts);
+                            Ok(())
+                        }
+                        Err(transaction_error) => Err(transaction_error.clone()),
+                    });
+
+                TransactionResult::new(
+                    result,
+                    details.log_messages.clone().unwrap_or_default(),
+                    details.return_data.clone(),
+                    details.inner_instructions.clone().unwrap_or_default(),
+                    details.executed_units,
+                )
+            }
+            TransactionExecutionResult::NotExecuted(transaction_error) => {
+                TransactionResult::not_executed(transaction_error.clone())
+            }
+        }
     }
 
     fn get_sysvar<T: Sysvar>(&self) -> T {
@@ -116,6 +293,212 @@ impl FuzzClient for TridentSVM {
     }
 
     fn clear_accounts(&mut self) {
-        self.clear_accounts();
+        reset_temp_accounts(self);
+        clear_signers();
+        clear_malicious_accounts();
+        clear_executed_transactions();
+    }
+
+    fn snapshot(&mut self) -> ClientSnapshot {
+        let client_id = client_id(self);
+        let accounts = get_written_accounts(&client_id)
+            .into_iter()
+            .map(|address| {
+                let account = FuzzClient::get_account(self, &address);
+                (address, account)
+            })
+            .collect();
+        let sysvars = snapshot_sysvars()
+            .iter()
+            .filter_map(|(address, _)| {
+                trident_svm::trident_svm::TridentSVM::get_account(self, address)
+                    .map(|account| (*address, account))
+            })
+            .collect();
+
+        ClientSnapshot {
+            client: client_id,
+            accounts,
+            sysvars,
+        }
+    }
+
+    fn restore(&mut self, snapshot: &ClientSnapshot) {
+        let client_id = client_id(self);
+        if snapshot.client != client_id {
+            panic!(
+                "The snapshot was taken by another client {}",
+                snapshot.client
+            )
+        }
+
+        // only the temporary accounts are written by the client, so dropping them
+        // removes all accounts written after the snapshot
+        reset_temp_accounts(self);
+
+        for (address, account) in &snapshot.accounts {
+            FuzzClient::set_account_custom(self, address, account);
+        }
+
+        // only the modified sysvars are set again, the sysvars set after the snapshot
+        // for the first time are kept
+        for (address, set_sysvar) in snapshot_sysvars() {
+            let Some((_, account)) = snapshot
+                .sysvars
+                .iter()
+                .find(|(sysvar_address, _)| *sysvar_address == address)
+            else {
+                continue;
+            };
+            let current = trident_svm::trident_svm::TridentSVM::get_account(self, &address);
+            if current.as_ref() != Some(account) {
+                set_sysvar(self, account);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+
+    thread_local! {
+        /// Init snapshot shared by the executors, as by the instances of the fuzz test.
+        static INIT_SNAPSHOT: RefCell<Option<ClientSnapshot>> = const { RefCell::new(None) };
+    }
+
+    /// Executes the iterations the same way as the flow executor, the init method is
+    /// executed only in the first one unless another client has taken the init snapshot.
+    struct Executor {
+        client: TridentSVM,
+        initialized: Pubkey,
+        written: Pubkey,
+    }
+
+    impl Executor {
+        fn new() -> Self {
+            Self {
+                client: TridentSVM::new(&[], &[], &[]),
+                initialized: Pubkey::new_unique(),
+                written: Pubkey::new_unique(),
+            }
+        }
+
+        fn init(&mut self) {
+            let account = AccountSharedData::new(100, 0, &Pubkey::default());
+            FuzzClient::set_account_custom(&mut self.client, &self.initialized, &account);
+            self.client.warp_to_timestamp(1_000);
+            self.client.set_sysvar(&Rent {
+                lamports_per_byte_year: 10,
+                ..Rent::default()
+            });
+        }
+
+        fn flow(&mut self) {
+            let account = AccountSharedData::new(1, 0, &Pubkey::default());
+            FuzzClient::set_account_custom(&mut self.client, &self.initialized, &account);
+            FuzzClient::set_account_custom(&mut self.client, &self.written, &account);
+            self.client.forward_in_time(60);
+            self.client.set_sysvar(&Rent {
+                lamports_per_byte_year: 20,
+                ..Rent::default()
+            });
+        }
+
+        /// Returns the lamports of the accounts, the timestamp and the rent seen by the flow.
+        fn execute_iteration(&mut self) -> (u64, u64, i64, u64) {
+            let init_snapshot = INIT_SNAPSHOT.with(|snapshot| {
+                snapshot
+                    .borrow_mut()
+                    .take()
+                    .filter(|snapshot| snapshot.is_taken_by(&self.client))
+            });
+            let init_snapshot = match init_snapshot {
+                Some(snapshot) => {
+                    self.client.restore(&snapshot);
+                    snapshot
+                }
+                None => {
+                    self.init();
+                    self.client.snapshot()
+                }
+            };
+            INIT_SNAPSHOT.with(|snapshot| *snapshot.borrow_mut() = Some(init_snapshot));
+
+            let state = (
+                FuzzClient::get_account(&mut self.client, &self.initialized).lamports(),
+                FuzzClient::get_account(&mut self.client, &self.written).lamports(),
+                FuzzClient::get_sysvar::<Clock>(&self.client).unix_timestamp,
+                FuzzClient::get_sysvar::<Rent>(&self.client).lamports_per_byte_year,
+            );
+            self.flow();
+            FuzzClient::clear_accounts(&mut self.client);
+            state
+        }
+    }
+
+    #[test]
+    fn test_iterations_restore_init_state() {
+        let mut executor = Executor::new();
+        let mut other_executor = Executor::new();
+
+        assert_eq!(executor.execute_iteration(), (100, 0, 1_000, 10));
+        assert_eq!(executor.execute_iteration(), (100, 0, 1_000, 10));
+        // the other client does not restore the snapshot taken by the first one
+        assert_eq!(other_executor.execute_iteration(), (100, 0, 1_000, 10));
+        assert_eq!(other_executor.execute_iteration(), (100, 0, 1_000, 10));
+        assert_eq!(executor.execute_iteration(), (100, 0, 1_000, 10));
+    }
+
+    #[test]
+    fn test_settled_permanent_accounts_kept_across_iterations() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let permanent = Pubkey::new_unique();
+        let temporary = Pubkey::new_unique();
+        register_permanent_accounts(&client_id(&client), &[permanent]);
+        let snapshot = client.snapshot();
+
+        let account = AccountSharedData::new(100, 0, &Pubkey::default());
+        settle_accounts(
+            &mut client,
+            &[(permanent, account.clone()), (temporary, account)],
+        );
+        assert_eq!(
+            FuzzClient::get_account(&mut client, &permanent).lamports(),
+            100
+        );
+        assert_eq!(
+            FuzzClient::get_account(&mut client, &temporary).lamports(),
+            100
+        );
+
+        FuzzClient::clear_accounts(&mut client);
+        assert_eq!(
+            FuzzClient::get_account(&mut client, &permanent).lamports(),
+            100
+        );
+        assert_eq!(
+            FuzzClient::get_account(&mut client, &temporary).lamports(),
+            0
+        );
+
+        client.restore(&snapshot);
+        assert_eq!(
+            FuzzClient::get_account(&mut client, &permanent).lamports(),
+            100
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "was taken by another client")]
+    fn test_restore_snapshot_of_other_client() {
+        let mut client = TridentSVM::new(&[], &[], &[]);
+        let mut other_client = TridentSVM::new(&[], &[], &[]);
+
+        let snapshot = client.snapshot();
+        assert!(!snapshot.is_taken_by(&other_client));
+        other_client.restore(&snapshot);
     }
 }
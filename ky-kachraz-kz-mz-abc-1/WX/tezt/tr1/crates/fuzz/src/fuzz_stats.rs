@@ -1,7 +1,46 @@
 #![allow(dead_code)]
 
 use prettytable::{row, Table};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::transaction::TransactionError;
+
+/// Number of the most frequent failure reasons shown for each transaction.
+const TOP_FAILURE_REASONS: usize = 3;
+
+/// Line output by the fuzz test after every fuzzing iteration, so the executions can be counted.
+const ITERATION_MARKER: &str = "trident:iteration";
+
+/// Number of significant digits the compute units are rounded down to in the histogram.
+const COMPUTE_UNITS_SIGNIFICANT_DIGITS: u32 = 3;
+
+/// Machine-readable formats the statistics can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    Json,
+    Csv,
+    Junit,
+}
+
+impl FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(StatsFormat::Json),
+            "csv" => Ok(StatsFormat::Csv),
+            "junit" => Ok(StatsFormat::Junit),
+            _ => Err(format!(
+                "unknown statistics format `{}`, expected one of: json, csv, junit",
+                s
+            )),
+        }
+    }
+}
 
 /// Represents fuzzing statistics, specifically tracking the number of times
 /// an instruction was invoked and successfully executed.
@@ -11,12 +50,105 @@ pub struct IterationStats {
     pub successful: u64,
     pub failed: u64,
     pub failed_check: u64,
+    /// Histogram of the kinds of errors the failed invocations ended with, see [`error_kind`].
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub errors: HashMap<String, u64>,
+    /// Weight the transaction is selected with by the `TransactionSelector`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+    /// Compute units consumed by the executed invocations.
+    #[serde(default, skip_serializing_if = "ComputeUnitsStats::is_empty")]
+    pub compute_units: ComputeUnitsStats,
+}
+
+/// Distribution of the compute units consumed by a transaction.
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ComputeUnitsStats {
+    pub count: u64,
+    pub total: u64,
+    pub min: u64,
+    pub max: u64,
+    /// Histogram of the compute units rounded down to three significant digits,
+    /// so the percentiles can be estimated after the statistics are merged.
+    pub histogram: BTreeMap<u64, u64>,
+}
+
+impl ComputeUnitsStats {
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Records the compute units consumed by one invocation.
+    pub fn record(&mut self, compute_units: u64) {
+        self.min = if self.is_empty() {
+            compute_units
+        } else {
+            self.min.min(compute_units)
+        };
+        self.max = self.max.max(compute_units);
+        self.count += 1;
+        self.total += compute_units;
+        *self
+            .histogram
+            .entry(histogram_bucket(compute_units))
+            .or_default() += 1;
+    }
+
+    /// Merges the compute units recorded by another fuzzing process.
+    pub fn merge(&mut self, other: &ComputeUnitsStats) {
+        if other.is_empty() {
+            return;
+        }
+        self.min = if self.is_empty() {
+            other.min
+        } else {
+            self.min.min(other.min)
+        };
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+        self.total += other.total;
+        for (bucket, count) in &other.histogram {
+            *self.histogram.entry(*bucket).or_default() += count;
+        }
+    }
+
+    pub fn mean(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.total / self.count
+        }
+    }
+
+    /// Estimates the percentile from the histogram, the estimate is never above the maximum.
+    /// # Arguments
+    /// * `percentile` - The percentile between 0 and 100.
+    pub fn percentile(&self, percentile: f64) -> u64 {
+        let rank = ((percentile / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, count) in &self.histogram {
+            seen += count;
+            if seen >= rank {
+                return (*bucket).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
 }
 
 /// Manages and aggregates statistics for fuzzing instructions.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct FuzzingStatistics {
+    /// Number of fuzzing sessions the statistics were collected from.
+    #[serde(default)]
+    pub sessions: u64,
+    /// Number of fuzzing iterations executed in all sessions.
+    #[serde(default)]
+    pub iterations: u64,
     pub instructions: HashMap<String, IterationStats>,
+    /// Names of the custom program errors, keyed by the error kinds.
+    #[serde(skip)]
+    error_names: HashMap<String, String>,
 }
 
 impl FuzzingStatistics {
@@ -24,15 +156,49 @@ impl FuzzingStatistics {
     pub fn new() -> Self {
         let empty_instructions = HashMap::<String, IterationStats>::default();
         Self {
+            sessions: 0,
+            iterations: 0,
             instructions: empty_instructions,
+            error_names: HashMap::default(),
         }
     }
+
+    /// Loads the statistics persisted by the previous fuzzing sessions.
+    /// Returns empty statistics if the file does not exist and an `InvalidData` error
+    /// if it cannot be parsed.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Persists the statistics as JSON. The file is replaced atomically, so an interrupted
+    /// checkpoint does not corrupt the statistics of the previous sessions.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let serialized = serde_json::to_string_pretty(self)?;
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serialized)?;
+        std::fs::rename(tmp_path, path)
+    }
     /// Outputs the statistics as a serialized JSON string.
     pub fn output_serialized(&self) {
         let serialized = serde_json::to_string(&self.instructions).unwrap();
         println!("{}", serialized);
     }
 
+    /// Outputs the marker of an executed fuzzing iteration if the statistics are collected.
+    pub fn output_iteration() {
+        if std::env::var("FUZZING_METRICS").is_ok() {
+            println!("{}", ITERATION_MARKER);
+        }
+    }
+
     /// Increments the invocation count for a given instruction.
     /// # Arguments
     /// * `instruction` - The instruction to increment the count for.
@@ -45,9 +211,36 @@ impl FuzzingStatistics {
                 successful: 0,
                 failed: 0,
                 failed_check: 0,
+                errors: HashMap::default(),
+                weight: None,
+                compute_units: ComputeUnitsStats::default(),
             });
     }
 
+    /// Sets the weight the instruction is selected with.
+    /// # Arguments
+    /// * `instruction` - The instruction to set the weight for.
+    /// * `weight` - The weight of the instruction.
+    pub fn set_weight(&mut self, instruction: String, weight: u32) {
+        if let Some(iterations_stats) = self.instructions.get_mut(&instruction) {
+            iterations_stats.weight = Some(weight);
+        }
+    }
+
+    /// Records the compute units consumed by the instruction.
+    /// # Arguments
+    /// * `instruction` - The instruction to record the compute units for.
+    /// * `compute_units` - The compute units consumed by the instruction.
+    pub fn record_compute_units(&mut self, instruction: String, compute_units: u64) {
+        // transactions rejected before their execution do not consume any compute units
+        if compute_units == 0 {
+            return;
+        }
+        if let Some(iterations_stats) = self.instructions.get_mut(&instruction) {
+            iterations_stats.compute_units.record(compute_units);
+        }
+    }
+
     /// Increments the successful invocation count for a given instruction.
     /// # Arguments
     /// * `instruction` - The instruction to increment the successful count for.
@@ -63,23 +256,32 @@ impl FuzzingStatistics {
                     successful: 1,
                     failed: 0,
                     failed_check: 0,
+                    errors: HashMap::default(),
+                    weight: None,
+                    compute_units: ComputeUnitsStats::default(),
                 },
             );
     }
-    pub fn increase_failed(&mut self, instruction: String) {
-        self.instructions
-            .entry(instruction)
-            .and_modify(|iterations_stats| iterations_stats.failed += 1)
-            .or_insert(
-                // this should not occure as instruction has to be invoked
-                // and then unsuccessfully_invoked
-                IterationStats {
-                    invoked: 1,
-                    successful: 0,
-                    failed: 1,
-                    failed_check: 0,
-                },
-            );
+    /// Increments the failed invocation count for a given instruction.
+    /// # Arguments
+    /// * `instruction` - The instruction to increment the failed count for.
+    /// * `error_kind` - The kind of error the transaction failed with, see [`error_kind`].
+    pub fn increase_failed(&mut self, instruction: String, error_kind: String) {
+        let iterations_stats = self.instructions.entry(instruction).or_insert(
+            // this should not occure as instruction has to be invoked
+            // and then unsuccessfully_invoked
+            IterationStats {
+                invoked: 1,
+                successful: 0,
+                failed: 0,
+                failed_check: 0,
+                errors: HashMap::default(),
+                weight: None,
+                compute_units: ComputeUnitsStats::default(),
+            },
+        );
+        iterations_stats.failed += 1;
+        *iterations_stats.errors.entry(error_kind).or_default() += 1;
     }
     pub fn increase_failed_check(&mut self, instruction: String) {
         self.instructions
@@ -93,6 +295,9 @@ impl FuzzingStatistics {
                     successful: 1,
                     failed: 0,
                     failed_check: 1,
+                    errors: HashMap::default(),
+                    weight: None,
+                    compute_units: ComputeUnitsStats::default(),
                 },
             );
     }
@@ -101,6 +306,11 @@ impl FuzzingStatistics {
     /// # Arguments
     /// * `serialized_iteration` - The serialized statistics to insert or update.
     pub fn insert_serialized(&mut self, serialized_iteration: &str) {
+        if serialized_iteration == ITERATION_MARKER {
+            self.iterations += 1;
+            return;
+        }
+
         let result = serde_json::from_str::<HashMap<String, IterationStats>>(serialized_iteration);
 
         if let Ok(deserialized_instruction) = result {
@@ -112,36 +322,458 @@ impl FuzzingStatistics {
                         instruction_stats.successful += value.successful;
                         instruction_stats.failed += value.failed;
                         instruction_stats.failed_check += value.failed_check;
+                        if value.weight.is_some() {
+                            instruction_stats.weight = value.weight;
+                        }
+                        instruction_stats.compute_units.merge(&value.compute_units);
+                        for (error_kind, count) in &value.errors {
+                            *instruction_stats
+                                .errors
+                                .entry(error_kind.clone())
+                                .or_default() += count;
+                        }
                     })
                     .or_insert_with(|| IterationStats {
                         invoked: value.invoked,
                         successful: value.successful,
                         failed: value.failed,
                         failed_check: value.failed_check,
+                        errors: value.errors.clone(),
+                        weight: value.weight,
+                        compute_units: value.compute_units.clone(),
                     });
             }
         }
     }
     /// Displays the collected statistics in a formatted table.
     pub fn show_table(&self) {
+        self.table().printstd();
+        println!("Note that unhandled panics are currently logged only as crashes and are not displayed in the table above.")
+    }
+
+    /// Total number of invoked transactions.
+    pub fn total_invoked(&self) -> u64 {
+        self.instructions.values().map(|stats| stats.invoked).sum()
+    }
+
+    /// Builds the table of the collected statistics, sorted by the instruction name.
+    pub fn table(&self) -> Table {
         let mut table = Table::new();
         table.add_row(row![
             "Instruction",
+            "Weight",
             "Invoked Total",
             "Ix Success",
             "Check Failed",
-            "Ix Failed"
+            "Ix Failed",
+            "Compute Units",
+            "Top Failure Reasons"
         ]);
-        for (instruction, stats) in &self.instructions {
+        for (instruction, stats) in self.sorted_instructions() {
+            let failure_reasons = self
+                .top_failure_reasons(stats)
+                .iter()
+                .map(|(reason, count)| {
+                    format!(
+                        "{}: {} ({:.1}%)",
+                        reason,
+                        count,
+                        *count as f64 * 100.0 / stats.failed as f64
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
             table.add_row(row![
                 instruction,
+                weight(stats),
                 stats.invoked,
                 stats.successful,
                 stats.failed_check,
                 stats.failed,
+                compute_units(stats),
+                failure_reasons,
             ]);
         }
-        table.printstd();
-        println!("Note that unhandled panics are currently logged only as crashes and are not displayed in the table above.")
+        table
+    }
+
+    /// Sets the names of the custom program errors, which are shown instead of the error codes.
+    /// # Arguments
+    /// * `error_names` - Names keyed by the error kinds of the custom errors, i.e. `Custom(<code>)@<program_id>`.
+    pub fn set_error_names(&mut self, error_names: HashMap<String, String>) {
+        self.error_names = error_names;
+    }
+
+    /// Returns the most frequent failure reasons of the transaction, the custom program errors
+    /// are named after the IDL of the program if it is known.
+    fn top_failure_reasons(&self, stats: &IterationStats) -> Vec<(String, u64)> {
+        let mut reasons: Vec<(String, u64)> = stats
+            .errors
+            .iter()
+            .map(|(error_kind, count)| (self.error_name(error_kind), *count))
+            .collect();
+        reasons.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        reasons.truncate(TOP_FAILURE_REASONS);
+        reasons
+    }
+
+    fn error_name(&self, error_kind: &str) -> String {
+        match self.error_names.get(error_kind) {
+            Some(name) => name.clone(),
+            // the program is not known, so only the error code is shown
+            None => error_kind
+                .split_once('@')
+                .map(|(error, _program_id)| error.to_string())
+                .unwrap_or_else(|| error_kind.to_string()),
+        }
+    }
+
+    /// Serializes the statistics into the given machine-readable format.
+    /// # Arguments
+    /// * `format` - The format of the output.
+    /// * `target` - Name of the fuzz test, used as the name of the JUnit test suite.
+    pub fn export(&self, format: StatsFormat, target: &str) -> String {
+        match format {
+            StatsFormat::Json => serde_json::to_string_pretty(self).unwrap(),
+            StatsFormat::Csv => self.to_csv(),
+            StatsFormat::Junit => self.to_junit(target),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "instruction,weight,invoked,successful,failed,failed_check,cu_min,cu_mean,cu_max,cu_p99,top_failure_reasons\n",
+        );
+        for (instruction, stats) in self.sorted_instructions() {
+            let failure_reasons = self
+                .top_failure_reasons(stats)
+                .iter()
+                .map(|(reason, count)| format!("{}={}", reason, count))
+                .collect::<Vec<_>>()
+                .join(";");
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                escape_csv(instruction),
+                weight(stats),
+                stats.invoked,
+                stats.successful,
+                stats.failed,
+                stats.failed_check,
+                stats.compute_units.min,
+                stats.compute_units.mean(),
+                stats.compute_units.max,
+                stats.compute_units.percentile(99.0),
+                escape_csv(&failure_reasons)
+            );
+        }
+        csv
+    }
+
+    /// Every transaction is a test case, which fails if any of its invariant checks failed.
+    fn to_junit(&self, target: &str) -> String {
+        let instructions = self.sorted_instructions();
+        let failures = instructions
+            .iter()
+            .filter(|(_, stats)| stats.failed_check > 0)
+            .count();
+        let target = escape_xml(target);
+
+        let mut junit = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            junit,
+            "<testsuites name=\"trident\" tests=\"{}\" failures=\"{}\">",
+            instructions.len(),
+            failures
+        );
+        let _ = writeln!(
+            junit,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            target,
+            instructions.len(),
+            failures
+        );
+        for (instruction, stats) in instructions {
+            let _ = writeln!(
+                junit,
+                "    <testcase name=\"{}\" classname=\"{}\">",
+                escape_xml(instruction),
+                target
+            );
+            if stats.failed_check > 0 {
+                let _ = writeln!(
+                    junit,
+                    "      <failure type=\"failed_check\" message=\"{} of {} invocations failed the invariant checks\"/>",
+                    stats.failed_check, stats.invoked
+                );
+            }
+            let failure_reasons = self.top_failure_reasons(stats).iter().fold(
+                String::new(),
+                |mut reasons, (reason, count)| {
+                    let _ = write!(reasons, ", {}: {}", reason, count);
+                    reasons
+                },
+            );
+            let _ = writeln!(
+                junit,
+                "      <system-out>invoked: {}, successful: {}, failed: {}, failed_check: {}{}</system-out>",
+                stats.invoked,
+                stats.successful,
+                stats.failed,
+                stats.failed_check,
+                escape_xml(&failure_reasons)
+            );
+            junit.push_str("    </testcase>\n");
+        }
+        junit.push_str("  </testsuite>\n</testsuites>\n");
+        junit
+    }
+
+    fn sorted_instructions(&self) -> Vec<(&String, &IterationStats)> {
+        let mut instructions: Vec<_> = self.instructions.iter().collect();
+        instructions.sort_by(|a, b| a.0.cmp(b.0));
+        instructions
+    }
+}
+
+/// Classifies the transaction error for the statistics. Custom program errors are identified
+/// by the error code and the program which returned them, i.e. `Custom(<code>)@<program_id>`,
+/// so they can be named after the IDL of the program. Other errors are identified by their
+/// variant name, e.g. `AccountNotFound` or `MissingRequiredSignature`.
+pub fn error_kind(error: &TransactionError, instructions: &[Instruction]) -> String {
+    match error {
+        TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+            match instructions.get(*index as usize) {
+                Some(instruction) => format!("Custom({})@{}", code, instruction.program_id),
+                None => format!("Custom({})", code),
+            }
+        }
+        TransactionError::InstructionError(_, instruction_error) => {
+            variant_name(&format!("{:?}", instruction_error))
+        }
+        _ => variant_name(&format!("{:?}", error)),
+    }
+}
+
+/// Strips the fields from the debug representation of an enum variant.
+fn variant_name(debug: &str) -> String {
+    debug
+        .split(['(', ' ', '{'])
+        .next()
+        .unwrap_or(debug)
+        .to_string()
+}
+
+/// Weight of the transaction, `-` if it was not selected by the `TransactionSelector`.
+fn weight(stats: &IterationStats) -> String {
+    stats
+        .weight
+        .map(|weight| weight.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Summary of the consumed compute units, `-` if the transaction was never executed.
+fn compute_units(stats: &IterationStats) -> String {
+    let compute_units = &stats.compute_units;
+    if compute_units.is_empty() {
+        return "-".to_string();
+    }
+    format!(
+        "min: {}\nmean: {}\nmax: {}\np99: {}",
+        compute_units.min,
+        compute_units.mean(),
+        compute_units.max,
+        compute_units.percentile(99.0)
+    )
+}
+
+/// Rounds the compute units down to the significant digits kept in the histogram.
+fn histogram_bucket(compute_units: u64) -> u64 {
+    let digits = compute_units.checked_ilog10().unwrap_or(0) + 1;
+    let scale = 10u64.pow(digits.saturating_sub(COMPUTE_UNITS_SIGNIFICANT_DIGITS));
+    compute_units / scale * scale
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compute_units(values: impl IntoIterator<Item = u64>) -> ComputeUnitsStats {
+        let mut stats = ComputeUnitsStats::default();
+        values
+            .into_iter()
+            .for_each(|compute_units| stats.record(compute_units));
+        stats
+    }
+
+    /// Serialized statistics of one transaction, as printed by the fuzzed binary.
+    fn transaction_line(name: &str, error_kind: Option<&str>, compute_units: u64) -> String {
+        let mut stats = FuzzingStatistics::new();
+        stats.increase_invoked(name.to_string());
+        stats.set_weight(name.to_string(), 2);
+        stats.record_compute_units(name.to_string(), compute_units);
+        match error_kind {
+            Some(error_kind) => stats.increase_failed(name.to_string(), error_kind.to_string()),
+            None => stats.increase_successful(name.to_string()),
+        }
+        serde_json::to_string(&stats.instructions).unwrap()
+    }
+
+    #[test]
+    fn test_histogram_bucket() {
+        assert_eq!(histogram_bucket(0), 0);
+        assert_eq!(histogram_bucket(7), 7);
+        assert_eq!(histogram_bucket(999), 999);
+        assert_eq!(histogram_bucket(1234), 1230);
+        assert_eq!(histogram_bucket(56_789), 56_700);
+        assert_eq!(histogram_bucket(1_400_000), 1_400_000);
+        assert_eq!(histogram_bucket(1_234_567), 1_230_000);
+    }
+
+    #[test]
+    fn test_compute_units_record() {
+        let stats = compute_units([1234, 1239, 5000, 800]);
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.total, 8273);
+        assert_eq!((stats.min, stats.max), (800, 5000));
+        assert_eq!(stats.mean(), 2068);
+        assert_eq!(
+            stats.histogram,
+            BTreeMap::from([(800, 1), (1230, 2), (5000, 1)])
+        );
+    }
+
+    #[test]
+    fn test_compute_units_percentile() {
+        let stats = compute_units(1..=1000);
+        assert_eq!(stats.percentile(99.0), 990);
+        assert_eq!(stats.percentile(50.0), 500);
+        assert_eq!(stats.percentile(100.0), 1000);
+        assert_eq!(stats.percentile(0.0), 1);
+
+        // the values are rounded down to the buckets
+        let stats = compute_units(1001..=2000);
+        assert_eq!(stats.percentile(99.0), 1990);
+
+        // the estimate stays within the recorded values
+        let stats = compute_units([12_345]);
+        assert_eq!(stats.percentile(99.0), 12_345);
+        assert_eq!(ComputeUnitsStats::default().percentile(99.0), 0);
+    }
+
+    #[test]
+    fn test_compute_units_merge() {
+        let mut merged = compute_units([5000, 1234]);
+        merged.merge(&compute_units([800, 1239, 70_000]));
+        merged.merge(&ComputeUnitsStats::default());
+
+        let recorded = compute_units([5000, 1234, 800, 1239, 70_000]);
+        assert_eq!(merged.count, recorded.count);
+        assert_eq!(merged.total, recorded.total);
+        assert_eq!((merged.min, merged.max), (800, 70_000));
+        assert_eq!(merged.histogram, recorded.histogram);
+        assert_eq!(merged.percentile(99.0), recorded.percentile(99.0));
+
+        let mut empty = ComputeUnitsStats::default();
+        empty.merge(&compute_units([1500]));
+        assert_eq!((empty.min, empty.max), (1500, 1500));
+    }
+
+    #[test]
+    fn test_insert_serialized() {
+        let mut stats = FuzzingStatistics::new();
+        for line in [
+            transaction_line("Deposit", None, 1500),
+            ITERATION_MARKER.to_string(),
+            transaction_line("Deposit", Some("Custom(1)"), 900),
+            transaction_line("Withdraw", Some("Custom(1)"), 700),
+            transaction_line("Deposit", Some("Custom(1)"), 1100),
+            ITERATION_MARKER.to_string(),
+            // output of the fuzzed program is ignored
+            "Program log: Instruction: Deposit".to_string(),
+        ] {
+            stats.insert_serialized(&line);
+        }
+
+        assert_eq!(stats.iterations, 2);
+        let deposit = &stats.instructions["Deposit"];
+        assert_eq!(
+            (deposit.invoked, deposit.successful, deposit.failed),
+            (3, 1, 2)
+        );
+        assert_eq!(
+            deposit.errors,
+            HashMap::from([("Custom(1)".to_string(), 2)])
+        );
+        assert_eq!(deposit.weight, Some(2));
+        assert_eq!(deposit.compute_units.count, 3);
+        assert_eq!(deposit.compute_units.max, 1500);
+        assert_eq!(stats.instructions["Withdraw"].failed, 1);
+    }
+
+    #[test]
+    fn test_merge_sessions() {
+        let path = std::env::temp_dir().join(format!(
+            "trident_stats_merge_sessions_{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        // the first session starts with empty statistics
+        let mut stats = FuzzingStatistics::load(&path).unwrap();
+        stats.sessions += 1;
+        stats.insert_serialized(&transaction_line("Deposit", None, 1500));
+        stats.insert_serialized(ITERATION_MARKER);
+        stats.save(&path).unwrap();
+
+        // the second session continues with the persisted statistics
+        let mut stats = FuzzingStatistics::load(&path).unwrap();
+        stats.sessions += 1;
+        stats.insert_serialized(&transaction_line("Deposit", Some("Custom(1)"), 3000));
+        stats.insert_serialized(ITERATION_MARKER);
+        stats.save(&path).unwrap();
+
+        let stats = FuzzingStatistics::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!((stats.sessions, stats.iterations), (2, 2));
+        let deposit = &stats.instructions["Deposit"];
+        assert_eq!(
+            (deposit.invoked, deposit.successful, deposit.failed),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            (deposit.compute_units.min, deposit.compute_units.max),
+            (1500, 3000)
+        );
+        assert_eq!(deposit.compute_units.percentile(99.0), 3000);
+    }
+
+    #[test]
+    fn test_load_invalid_statistics() {
+        let path =
+            std::env::temp_dir().join(format!("trident_stats_invalid_{}.json", std::process::id()));
+        std::fs::write(&path, "{ \"instructions\": ").unwrap();
+        let error = FuzzingStatistics::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
     }
 }
@@ -1,8 +1,19 @@
 pub mod accounts_storage;
+pub mod authorization_attacks;
+pub mod builtin_invariants;
+pub mod client_snapshot;
+pub mod coverage;
+pub mod crash_report;
 pub mod error;
+pub mod flow_invariants;
 pub mod fuzz_client_impl;
 pub mod fuzz_stats;
+pub mod libfuzzer;
+pub mod replay_log;
 pub mod traits;
+pub mod transaction_result;
+pub mod transaction_sequence;
+pub mod transaction_weights;
 
 pub mod trident_accounts;
 
@@ -10,6 +21,10 @@ pub mod trident_pubkey;
 
 pub mod types;
 
+// links the libFuzzer runtime
+#[cfg(libfuzzer)]
+extern crate libfuzzer_sys;
+
 pub mod fuzzing {
     /// solana_sdk
     pub use solana_sdk;
@@ -28,6 +43,8 @@ pub mod fuzzing {
     pub use solana_sdk::transaction::Transaction;
     pub use solana_sdk::transaction::TransactionError;
 
+    pub use super::coverage::fuzz as fuzz_coverage;
+    pub use super::libfuzzer::fuzz as fuzz_libfuzzer;
     /// fuzzing
     pub use afl::fuzz as fuzz_afl;
     pub use arbitrary;
@@ -36,6 +53,7 @@ pub mod fuzzing {
 
     /// trident traits
     pub use super::traits::AccountsMethods;
+    pub use super::traits::Discriminator;
     pub use super::traits::FuzzClient;
     pub use super::traits::InstructionGetters;
     pub use super::traits::InstructionHooks;
@@ -53,6 +71,7 @@ pub mod fuzzing {
     pub use trident_derive_flow_executor::flow_executor;
     pub use trident_derive_flow_executor::flow_ignore;
     pub use trident_derive_flow_executor::init;
+    pub use trident_derive_flow_executor::invariant;
 
     pub use trident_derive_instruction::TridentInstruction;
     pub use trident_derive_remaining_accounts::TridentRemainingAccounts;
@@ -63,13 +82,44 @@ pub mod fuzzing {
 
     /// accounts storages
     pub use super::accounts_storage::account_storage::AccountsStorage;
+    pub use super::accounts_storage::malicious_account::AccountForgery;
+    pub use super::accounts_storage::reset_storage_names;
     pub use super::accounts_storage::AccountMetadata;
     pub use super::accounts_storage::PdaSeeds;
 
+    /// authorization attacks
+    pub use super::authorization_attacks::AccountAttack;
+    pub use super::authorization_attacks::AttackTarget;
+
+    /// built-in invariants
+    pub use super::builtin_invariants::enable_builtin_invariants;
+    pub use super::builtin_invariants::set_compute_unit_threshold;
+    pub use super::builtin_invariants::BuiltinInvariant;
+
+    /// flow invariants
+    pub use super::flow_invariants::check_flow_invariants;
+    pub use super::flow_invariants::set_flow_invariants;
+
+    /// client snapshots
+    pub use super::client_snapshot::ClientSnapshot;
+
+    /// transaction result
+    pub use super::transaction_result::TransactionResult;
+
+    /// transaction sequence
+    pub use super::transaction_sequence::random_transactions_count;
+
+    /// transaction weights
+    pub use super::transaction_weights::select_weighted;
+    pub use super::transaction_weights::set_transaction_weights;
+    pub use super::transaction_weights::transaction_weights;
+
     pub use trident_config::TridentConfig;
 
+    pub use super::crash_report::CrashReport;
     pub use super::error::*;
     pub use super::fuzz_stats::FuzzingStatistics;
+    pub use super::replay_log::ReplayLog;
 
     pub use std::cell::RefCell;
     pub use std::collections::HashMap;
@@ -81,6 +131,7 @@ pub mod fuzzing {
     pub use crate::types::FuzzerData;
 
     /// trident accounts
+    pub use crate::trident_accounts::SnapshotAccount;
     pub use crate::trident_accounts::TridentAccount;
     pub use crate::trident_pubkey::TridentPubkey;
 
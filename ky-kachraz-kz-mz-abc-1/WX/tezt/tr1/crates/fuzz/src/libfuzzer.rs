@@ -0,0 +1,91 @@
+//! libFuzzer runtime driving the fuzz test.
+//!
+//! The fuzz test binary defines its own `main`, so instead of the `fuzz_target!`
+//! entry point the fuzzing loop is started with `LLVMFuzzerRunDriver`, which
+//! parses the command line options the same way the libFuzzer `main` does.
+
+/// Starts libFuzzer with the command line arguments of the current process and
+/// executes the closure for every generated input.
+///
+/// Any panic aborts the process, so libFuzzer stores the input as a crash artifact,
+/// including the panics of the programs which are caught by the SVM.
+#[cfg(libfuzzer)]
+pub fn fuzz<F>(mut closure: F)
+where
+    F: FnMut(&[u8]),
+{
+    use std::ffi::{c_char, c_int, CString};
+
+    extern "C" {
+        fn LLVMFuzzerRunDriver(
+            argc: *mut c_int,
+            argv: *mut *mut *mut c_char,
+            callback: extern "C" fn(*const u8, usize) -> c_int,
+        ) -> c_int;
+    }
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        default_hook(panic_info);
+        std::process::abort();
+    }));
+
+    let args: Vec<CString> = std::env::args()
+        .map(|arg| CString::new(arg).expect("Command line argument contains a nul byte"))
+        .collect();
+    let mut argv: Vec<*mut c_char> = args.iter().map(|arg| arg.as_ptr() as *mut _).collect();
+    argv.push(std::ptr::null_mut());
+    let mut argc = args.len() as c_int;
+    let mut argv = argv.as_mut_ptr();
+
+    // libFuzzer calls the callback on the current thread only, the closure
+    // outlives the driver so the pointer stays valid
+    CLOSURE.with(|current| current.set(&mut closure as *mut F as *mut ()));
+    let exit_code = unsafe { LLVMFuzzerRunDriver(&mut argc, &mut argv, test_one_input::<F>) };
+    CLOSURE.with(|current| current.set(std::ptr::null_mut()));
+
+    std::process::exit(exit_code);
+}
+
+/// The fuzz test has to be compiled with `--cfg libfuzzer`, which links the libFuzzer runtime.
+#[cfg(not(libfuzzer))]
+pub fn fuzz<F>(_closure: F)
+where
+    F: FnMut(&[u8]),
+{
+    panic!("libFuzzer runtime is not linked, run the fuzz test with `trident fuzz run-libfuzzer`")
+}
+
+#[cfg(libfuzzer)]
+thread_local! {
+    static CLOSURE: std::cell::Cell<*mut ()> = const { std::cell::Cell::new(std::ptr::null_mut()) };
+}
+
+#[cfg(libfuzzer)]
+extern "C" fn test_one_input<F>(data: *const u8, size: usize) -> std::ffi::c_int
+where
+    F: FnMut(&[u8]),
+{
+    let closure = CLOSURE.with(|current| current.get()) as *mut F;
+    let data = if size == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(data, size) }
+    };
+    // unwinding across the FFI boundary is not allowed
+    if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe { (*closure)(data) }))
+        .is_err()
+    {
+        std::process::abort();
+    }
+    0
+}
+
+/// Entry point expected by the `libfuzzer-sys` wrapper. It is never called, because
+/// the inputs are passed directly to the closure given to [`fuzz`].
+#[cfg(libfuzzer)]
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn rust_fuzzer_test_input(_bytes: &[u8]) -> i32 {
+    std::process::abort();
+}
@@ -0,0 +1,357 @@
+use std::cell::RefCell;
+
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::crash_report::panic_message;
+use crate::error::FuzzingError;
+use crate::traits::TransactionGetters;
+
+/// Environment variable containing the path the replay log is written to.
+pub const TRIDENT_REPLAY_LOG: &str = "TRIDENT_REPLAY_LOG";
+
+thread_local! {
+    static REPLAY_LOG: RefCell<Option<ReplayLog>> = const { RefCell::new(None) };
+}
+
+/// Structured log of a crash file replay, containing every flow and transaction
+/// which was executed together with its effects on the accounts.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ReplayLog {
+    pub flows: Vec<FlowLog>,
+    pub result: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct FlowLog {
+    pub name: String,
+    pub transactions: Vec<TransactionLog>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TransactionLog {
+    pub name: String,
+    /// Rust code constructing the transaction with the same instruction data and account ids.
+    pub rust_code: String,
+    /// Whether the transaction was executed with hooks.
+    pub hooks: bool,
+    pub instructions: Vec<InstructionLog>,
+    pub account_diffs: Vec<AccountDiff>,
+    pub outcome: TransactionOutcome,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct InstructionLog {
+    pub program_id: String,
+    /// Hex encoded instruction data including the discriminator.
+    pub data: String,
+    pub accounts: Vec<AccountMetaLog>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AccountMetaLog {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AccountDiff {
+    pub address: String,
+    pub lamports_before: u64,
+    pub lamports_after: u64,
+    pub owner_before: String,
+    pub owner_after: String,
+    pub data_len_before: usize,
+    pub data_len_after: usize,
+    pub data_changes: Vec<DataChange>,
+}
+
+/// Contiguous range of bytes which differ before and after the transaction.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct DataChange {
+    pub offset: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub enum TransactionOutcome {
+    Success,
+    Failed(String),
+    InvariantFailed(String),
+}
+
+impl ReplayLog {
+    /// Starts recording of the replay log for the current thread.
+    pub fn start() {
+        REPLAY_LOG.with(|log| *log.borrow_mut() = Some(ReplayLog::default()));
+    }
+
+    /// Records the start of a new flow.
+    pub fn flow(name: &str) {
+        REPLAY_LOG.with(|log| {
+            if let Some(log) = log.borrow_mut().as_mut() {
+                log.flows.push(FlowLog {
+                    name: name.to_string(),
+                    transactions: vec![],
+                });
+            }
+        });
+    }
+
+    /// Stops recording, shows the replay log in the terminal and writes it as JSON
+    /// to the file specified by the `TRIDENT_REPLAY_LOG` environment variable.
+    pub fn finish(result: &std::thread::Result<Result<(), FuzzingError>>) {
+        Self::finish_with(match result {
+            Ok(Ok(_)) => "Finished".to_string(),
+            Ok(Err(e)) => e.to_string().trim().to_string(),
+            Err(panic) => format!("Panicked: {}", panic_message(panic.as_ref())),
+        });
+    }
+
+    /// Finishes the replay log within the panic hook, as AFL and libFuzzer abort
+    /// the process on panic before the replay log would be finished otherwise.
+    pub fn finish_on_panic() {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            previous_hook(panic_info);
+            Self::finish_with(format!("Panicked: {}", panic_message(panic_info.payload())));
+        }));
+    }
+
+    fn finish_with(result: String) {
+        let log = REPLAY_LOG.with(|log| log.borrow_mut().take());
+
+        if let Some(mut log) = log {
+            log.result = result;
+
+            log.show();
+
+            if let Ok(path) = std::env::var(TRIDENT_REPLAY_LOG) {
+                match serde_json::to_string_pretty(&log) {
+                    Ok(serialized) => match std::fs::write(&path, serialized) {
+                        Ok(_) => println!("\x1b[92mFinished\x1b[0m Replay log saved to [{}]", path),
+                        Err(e) => eprintln!("Unable to save the replay log to [{}]: {}", path, e),
+                    },
+                    Err(e) => eprintln!("Unable to serialize the replay log: {}", e),
+                }
+            }
+        }
+    }
+
+    fn is_recording() -> bool {
+        REPLAY_LOG.with(|log| log.borrow().is_some())
+    }
+
+    fn record(transaction: TransactionLog) {
+        REPLAY_LOG.with(|log| {
+            if let Some(log) = log.borrow_mut().as_mut() {
+                // transactions executed outside of any flow, e.g. within init
+                if log.flows.is_empty() {
+                    log.flows.push(FlowLog {
+                        name: "init".to_string(),
+                        transactions: vec![],
+                    });
+                }
+                if let Some(flow) = log.flows.last_mut() {
+                    flow.transactions.push(transaction);
+                }
+            }
+        });
+    }
+
+    fn show(&self) {
+        println!("\x1b[96mReplay log\x1b[0m");
+        for flow in &self.flows {
+            println!("Flow \x1b[1m{}\x1b[0m", flow.name);
+            for (index, transaction) in flow.transactions.iter().enumerate() {
+                let outcome = match &transaction.outcome {
+                    TransactionOutcome::Success => "\x1b[92mSuccess\x1b[0m".to_string(),
+                    TransactionOutcome::Failed(e) => format!("\x1b[33mFailed\x1b[0m: {}", e),
+                    TransactionOutcome::InvariantFailed(e) => {
+                        format!("\x1b[31mInvariant failed\x1b[0m: {}", e.trim())
+                    }
+                };
+                println!("  [{}] {} - {}", index, transaction.name, outcome);
+
+                for instruction in &transaction.instructions {
+                    println!("      program: {}", instruction.program_id);
+                    println!("      data: {}", instruction.data);
+                    for meta in &instruction.accounts {
+                        println!(
+                            "        {} {}{}",
+                            meta.pubkey,
+                            if meta.is_signer { "signer " } else { "" },
+                            if meta.is_writable {
+                                "writable"
+                            } else {
+                                "readonly"
+                            }
+                        );
+                    }
+                }
+
+                for diff in &transaction.account_diffs {
+                    println!("      \x1b[93m{}\x1b[0m", diff.address);
+                    if diff.lamports_before != diff.lamports_after {
+                        println!(
+                            "        lamports: {} -> {}",
+                            diff.lamports_before, diff.lamports_after
+                        );
+                    }
+                    if diff.owner_before != diff.owner_after {
+                        println!(
+                            "        owner: {} -> {}",
+                            diff.owner_before, diff.owner_after
+                        );
+                    }
+                    if diff.data_len_before != diff.data_len_after {
+                        println!(
+                            "        data length: {} -> {}",
+                            diff.data_len_before, diff.data_len_after
+                        );
+                    }
+                    for change in &diff.data_changes {
+                        println!(
+                            "        data[{}..]: {} -> {}",
+                            change.offset, change.before, change.after
+                        );
+                    }
+                }
+            }
+        }
+        println!("Result: {}", self.result);
+    }
+}
+
+/// Records the executed transaction together with the differences of its account snapshots,
+/// if the replay log is recorded.
+pub(crate) fn record_transaction(
+    transaction: &(impl TransactionGetters + ?Sized),
+    instructions: &[Instruction],
+    hooks: bool,
+    outcome: TransactionOutcome,
+) {
+    if !ReplayLog::is_recording() {
+        return;
+    }
+
+    // the accounts repeated within the instructions share the same snapshots
+    let mut addresses: Vec<Pubkey> = vec![];
+    let account_diffs = transaction
+        .get_account_snapshots()
+        .into_iter()
+        .filter(|(before, _)| {
+            let is_new = !addresses.contains(&before.pubkey());
+            addresses.push(before.pubkey());
+            is_new
+        })
+        .filter_map(|(before, after)| {
+            account_diff(&before.pubkey(), before.get_account(), after.get_account())
+        })
+        .collect();
+
+    let instructions = instructions
+        .iter()
+        .map(|instruction| InstructionLog {
+            program_id: instruction.program_id.to_string(),
+            data: to_hex(&instruction.data),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| AccountMetaLog {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+        })
+        .collect();
+
+    ReplayLog::record(TransactionLog {
+        name: transaction.get_transaction_name(),
+        rust_code: transaction.to_rust_code(),
+        hooks,
+        instructions,
+        account_diffs,
+        outcome,
+    });
+}
+
+/// Marks the last recorded transaction as failed by the invariant check performed after it.
+pub(crate) fn record_invariant_failure(message: &str) {
+    REPLAY_LOG.with(|log| {
+        if let Some(transaction) = log
+            .borrow_mut()
+            .as_mut()
+            .and_then(|log| log.flows.last_mut())
+            .and_then(|flow| flow.transactions.last_mut())
+        {
+            transaction.outcome = TransactionOutcome::InvariantFailed(message.to_string());
+        }
+    });
+}
+
+fn account_diff(
+    address: &Pubkey,
+    before: &AccountSharedData,
+    after: &AccountSharedData,
+) -> Option<AccountDiff> {
+    // the rent epoch is updated by the SVM, it is not a change made by the transaction
+    if before.lamports() == after.lamports()
+        && before.owner() == after.owner()
+        && before.data() == after.data()
+    {
+        return None;
+    }
+
+    let mut data_changes = vec![];
+    let (data_before, data_after) = (before.data(), after.data());
+    let mut offset = 0;
+    while offset < data_before.len().max(data_after.len()) {
+        if data_before.get(offset) == data_after.get(offset) {
+            offset += 1;
+            continue;
+        }
+        let start = offset;
+        while offset < data_before.len().max(data_after.len())
+            && data_before.get(offset) != data_after.get(offset)
+        {
+            offset += 1;
+        }
+        data_changes.push(DataChange {
+            offset: start,
+            before: to_hex(
+                data_before
+                    .get(start..offset.min(data_before.len()))
+                    .unwrap_or_default(),
+            ),
+            after: to_hex(
+                data_after
+                    .get(start..offset.min(data_after.len()))
+                    .unwrap_or_default(),
+            ),
+        });
+    }
+
+    Some(AccountDiff {
+        address: address.to_string(),
+        lamports_before: before.lamports(),
+        lamports_after: after.lamports(),
+        owner_before: before.owner().to_string(),
+        owner_after: after.owner().to_string(),
+        data_len_before: data_before.len(),
+        data_len_after: data_after.len(),
+        data_changes,
+    })
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().fold(String::new(), |mut hex, byte| {
+        hex.push_str(&format!("{:02x}", byte));
+        hex
+    })
+}
@@ -1,6 +1,8 @@
 use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
 
 use super::FuzzClient;
+use crate::authorization_attacks::AttackTarget;
+use crate::trident_accounts::SnapshotAccount;
 
 pub trait AccountsMethods {
     type IxAccounts;
@@ -29,4 +31,20 @@ pub trait AccountsMethods {
     /// Capture the state of accounts after transaction execution
     #[doc(hidden)]
     fn capture_after(&mut self, client: &mut impl FuzzClient);
+
+    /// Rust code constructing the accounts with the same account ids
+    #[doc(hidden)]
+    fn to_rust_code(&self) -> String;
+
+    /// Snapshots of the accounts before and after the successful execution
+    #[doc(hidden)]
+    fn account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+        Vec::new()
+    }
+
+    /// Authorization attacks applicable to the accounts, in the order of the account metas
+    #[doc(hidden)]
+    fn account_attacks(&self) -> Vec<AttackTarget> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,9 @@
+/// Program account or event type identified by the discriminator at the beginning of its data.
+///
+/// Implemented by the types generated from the `accounts` and `events` sections of the program
+/// IDL, see [`SnapshotAccount::deserialize`](crate::trident_accounts::SnapshotAccount::deserialize)
+/// and [`TransactionResult::events`](crate::transaction_result::TransactionResult::events).
+pub trait Discriminator {
+    /// The 8-byte Anchor discriminator of the type.
+    const DISCRIMINATOR: &'static [u8];
+}
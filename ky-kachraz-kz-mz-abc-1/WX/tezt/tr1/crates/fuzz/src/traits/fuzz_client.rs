@@ -6,13 +6,15 @@ use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::sysvar::Sysvar;
-use solana_sdk::transaction::TransactionError;
 
 use trident_config::TridentConfig;
 use trident_svm::utils::ProgramEntrypoint;
 
+use crate::client_snapshot::ClientSnapshot;
+use crate::transaction_result::TransactionResult;
+
 /// A trait providing methods to read and write (manipulate) accounts
-pub trait FuzzClient {
+pub trait FuzzClient: 'static {
     /// Deploy a native program
     fn deploy_native_program(&mut self, program: ProgramEntrypoint);
 
@@ -40,6 +42,10 @@ pub trait FuzzClient {
     /// Get the Keypair of the client's payer account
     fn payer(&self) -> Keypair;
 
+    /// Register a keypair the transactions are signed with if its account is a required signer,
+    /// e.g. an account created by `set_account_custom` outside of the `AccountsStorage`
+    fn add_signer(&mut self, keypair: &Keypair);
+
     /// Get the account at the given address
     fn get_account(&mut self, key: &Pubkey) -> AccountSharedData;
 
@@ -47,11 +53,17 @@ pub trait FuzzClient {
     fn get_last_blockhash(&self) -> Hash;
 
     /// Send a transaction and return until the transaction has been finalized or rejected.
-    fn process_instructions(
-        &mut self,
-        _instructions: &[Instruction],
-    ) -> Result<(), TransactionError>;
+    /// The result contains the program logs, return data, inner instructions and consumed
+    /// compute units of the transaction.
+    fn process_instructions(&mut self, _instructions: &[Instruction]) -> TransactionResult;
 
     // Clear Temp account created during fuzzing iteration
     fn clear_accounts(&mut self);
+
+    /// Capture the accounts written by the client and the sysvars, so they can be restored
+    fn snapshot(&mut self) -> ClientSnapshot;
+
+    /// Restore the accounts and the sysvars captured by the snapshot, the accounts written
+    /// after the snapshot are removed. Programs deployed after the snapshot are kept.
+    fn restore(&mut self, snapshot: &ClientSnapshot);
 }
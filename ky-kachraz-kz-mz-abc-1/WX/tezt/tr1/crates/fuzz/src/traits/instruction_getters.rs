@@ -1,6 +1,8 @@
 use solana_sdk::instruction::AccountMeta;
 
 use super::InstructionHooks;
+use crate::authorization_attacks::AttackTarget;
+use crate::trident_accounts::SnapshotAccount;
 
 pub trait InstructionGetters: InstructionHooks {
     #[doc(hidden)]
@@ -14,4 +16,20 @@ pub trait InstructionGetters: InstructionHooks {
     #[doc(hidden)]
     /// Convert accounts to account metas
     fn to_account_metas(&mut self) -> Vec<AccountMeta>;
+
+    #[doc(hidden)]
+    /// Rust code constructing the instruction with the same data and account ids
+    fn to_rust_code(&self) -> String;
+
+    #[doc(hidden)]
+    /// Authorization attacks applicable to the accounts, in the order of the account metas
+    fn get_attack_targets(&self) -> Vec<AttackTarget> {
+        Vec::new()
+    }
+
+    #[doc(hidden)]
+    /// Snapshots of the accounts before and after the successful execution
+    fn get_account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+        Vec::new()
+    }
 }
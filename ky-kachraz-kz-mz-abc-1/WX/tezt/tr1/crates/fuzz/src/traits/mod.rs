@@ -1,4 +1,5 @@
 pub mod account;
+pub mod discriminator;
 pub mod fuzz_client;
 pub mod instruction_getters;
 pub mod instruction_hooks;
@@ -12,6 +13,7 @@ pub mod transaction_selector;
 pub mod transaction_setters;
 
 pub use account::*;
+pub use discriminator::*;
 pub use fuzz_client::*;
 pub use instruction_getters::*;
 pub use instruction_hooks::*;
@@ -1,6 +1,7 @@
 use solana_sdk::instruction::AccountMeta;
 
 use super::FuzzClient;
+use crate::trident_accounts::SnapshotAccount;
 
 pub trait RemainingAccountsMethods {
     #[doc(hidden)]
@@ -11,4 +12,12 @@ pub trait RemainingAccountsMethods {
 
     #[doc(hidden)]
     fn capture_after(&mut self, client: &mut impl FuzzClient);
+
+    #[doc(hidden)]
+    fn to_rust_code(&self) -> String;
+
+    #[doc(hidden)]
+    fn account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+        Vec::new()
+    }
 }
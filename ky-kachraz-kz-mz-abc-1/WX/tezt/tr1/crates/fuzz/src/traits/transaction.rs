@@ -2,9 +2,19 @@ use super::transaction_private::TransactionPrivateMethods;
 use super::TransactionGetters;
 use super::TransactionHooks;
 use super::TransactionSetters;
+use crate::builtin_invariants::{
+    check_builtin_invariants, check_failed_transaction_compute_units, AccountsBefore,
+};
+use crate::crash_report::CrashReport;
 use crate::error::*;
+use crate::flow_invariants::schedule_flow_invariants;
+use crate::fuzz_stats::error_kind;
 use crate::fuzzing::FuzzingStatistics;
+use crate::traits::Discriminator;
 use crate::traits::FuzzClient;
+use crate::transaction_result::{get_transaction_result, TransactionResult};
+use crate::transaction_sequence::is_duplicate_transaction;
+use crate::transaction_weights::selection_weight;
 use crate::types::FuzzerData;
 
 use solana_sdk::transaction::TransactionError;
@@ -34,24 +44,40 @@ pub trait TransactionMethods:
     ///
     /// This method handles the complete transaction lifecycle:
     /// - Creates transaction instructions
+    /// - Fails the transaction as already processed if it is a disallowed duplicate
     /// - Takes account snapshots before execution
     /// - Runs pre-transaction hooks
+    /// - Runs authorization attacks if enabled
     /// - Processes the transaction
     /// - Takes account snapshots after execution
-    /// - Performs invariant checks
+    /// - Performs invariant checks and schedules the check of the `#[invariant]` functions
+    ///   of the flow executor, performed once the next transaction is built or the flows end
     /// - Runs post-transaction hooks
     /// - Handles any errors
     fn execute(&mut self, client: &mut impl FuzzClient) -> Result<(), FuzzingError> {
+        CrashReport::set_current_transaction(self.get_transaction_name());
+
         let instructions = self.create_transaction(client);
 
+        // The transaction identical to an already executed one fails as already processed
+        // before its accounts are captured, if the duplicate transactions are not allowed
+        if is_duplicate_transaction(&instructions) {
+            let error = self.reject_duplicate_transaction(client, &instructions, true);
+            self.transaction_error_handler(error)?;
+            return Ok(());
+        }
+
         let fuzzing_metrics = std::env::var("FUZZING_METRICS");
 
         // If stats are enabled, use the stats logger
         if fuzzing_metrics.is_ok() {
             let mut stats_logger = FuzzingStatistics::new();
 
-            // Record transaction invocation
+            // Record transaction invocation and the weight it was selected with
             stats_logger.increase_invoked(self.get_transaction_name());
+            if let Some(weight) = selection_weight(&self.get_transaction_name()) {
+                stats_logger.set_weight(self.get_transaction_name(), weight);
+            }
 
             // Run pre-transaction hook
             self.pre_transaction(client);
@@ -59,30 +85,45 @@ pub trait TransactionMethods:
             // Take snapshot of accounts before execution
             self.set_snapshot_before(client);
 
-            // Execute the transaction
-            let tx_result = client.process_instructions(&instructions);
+            // Run authorization attacks if enabled
+            self.attack_authorization(client, &instructions);
+
+            // Capture accounts for the built-in invariants if they are enabled
+            let accounts_before = AccountsBefore::capture(client, &instructions);
 
-            match tx_result {
+            // Execute the transaction, take snapshot of accounts after the successful
+            // execution and record the transaction in the replay log
+            let tx_result = self.process_transaction(client, &instructions, true);
+
+            // Record the consumed compute units
+            stats_logger.record_compute_units(
+                self.get_transaction_name(),
+                tx_result.compute_units_consumed(),
+            );
+
+            match tx_result.into_result() {
                 Ok(_) => {
                     // Record successful execution
                     stats_logger.increase_successful(self.get_transaction_name());
 
-                    // Take snapshot of accounts after execution
-                    self.set_snapshot_after(client);
-
-                    // Run invariant checks
-                    if let Err(e) = self.transaction_invariant_check() {
+                    // Run invariant checks, including the check of accepted malicious accounts
+                    // and the built-in invariants
+                    if let Err(e) = self
+                        .check_malicious_accounts(&instructions)
+                        .and_then(|_| check_builtin_invariants(&accounts_before, client))
+                        .and_then(|_| self.transaction_invariant_check())
+                    {
                         // Record check failure
                         stats_logger.increase_failed_check(self.get_transaction_name());
                         stats_logger.output_serialized();
 
-                        eprintln!(
-                            "\x1b[31mCRASH DETECTED!\x1b[0m Custom check after the {} transaction did not pass!",
-                            self.get_transaction_name()
-                        );
-                        panic!("{}", e)
+                        self.invariant_check_failed(e)
                     }
 
+                    // The accounts are borrowed by the flow, the `#[invariant]` functions
+                    // of the flow executor are checked on them once they are available
+                    schedule_flow_invariants(self.get_transaction_name());
+
                     // Output statistics
                     stats_logger.output_serialized();
 
@@ -91,7 +132,18 @@ pub trait TransactionMethods:
                 }
                 Err(e) => {
                     // Record transaction failure
-                    stats_logger.increase_failed(self.get_transaction_name());
+                    stats_logger.increase_failed(
+                        self.get_transaction_name(),
+                        error_kind(&e, &instructions),
+                    );
+
+                    // The failed transaction must not run out of compute units
+                    if let Err(check_error) = check_failed_transaction_compute_units(&e) {
+                        stats_logger.increase_failed_check(self.get_transaction_name());
+                        stats_logger.output_serialized();
+
+                        self.invariant_check_failed(check_error)
+                    }
                     stats_logger.output_serialized();
 
                     // Handle transaction error
@@ -105,27 +157,41 @@ pub trait TransactionMethods:
             // Run pre-transaction hook
             self.pre_transaction(client);
 
-            // Execute the transaction
-            let tx_result = client.process_instructions(&instructions);
+            // Run authorization attacks if enabled
+            self.attack_authorization(client, &instructions);
+
+            // Capture accounts for the built-in invariants if they are enabled
+            let accounts_before = AccountsBefore::capture(client, &instructions);
 
-            match tx_result {
+            // Execute the transaction, take snapshot of accounts after the successful
+            // execution and record the transaction in the replay log
+            let tx_result = self.process_transaction(client, &instructions, true);
+
+            match tx_result.into_result() {
                 Ok(_) => {
-                    // Take snapshot of accounts after execution
-                    self.set_snapshot_after(client);
-
-                    // Run invariant checks
-                    if let Err(e) = self.transaction_invariant_check() {
-                        eprintln!(
-                            "\x1b[31mCRASH DETECTED!\x1b[0m Custom check after the {} transaction did not pass!",
-                            self.get_transaction_name()
-                        );
-                        panic!("{}", e)
+                    // Run invariant checks, including the check of accepted malicious accounts
+                    // and the built-in invariants
+                    if let Err(e) = self
+                        .check_malicious_accounts(&instructions)
+                        .and_then(|_| check_builtin_invariants(&accounts_before, client))
+                        .and_then(|_| self.transaction_invariant_check())
+                    {
+                        self.invariant_check_failed(e)
                     }
 
+                    // The accounts are borrowed by the flow, the `#[invariant]` functions
+                    // of the flow executor are checked on them once they are available
+                    schedule_flow_invariants(self.get_transaction_name());
+
                     // Run post-transaction hook
                     self.post_transaction(client);
                 }
                 Err(e) => {
+                    // The failed transaction must not run out of compute units
+                    if let Err(check_error) = check_failed_transaction_compute_units(&e) {
+                        self.invariant_check_failed(check_error)
+                    }
+
                     // Handle transaction error
                     self.transaction_error_handler(e)?
                 }
@@ -135,47 +201,90 @@ pub trait TransactionMethods:
         Ok(())
     }
 
+    /// Returns the result of the last execution of the transaction, including the program logs,
+    /// return data, inner instructions and consumed compute units
+    ///
+    /// Available within `post_transaction`, `transaction_invariant_check` and
+    /// `transaction_error_handler` hooks.
+    fn get_transaction_result(&self) -> TransactionResult {
+        get_transaction_result()
+    }
+
+    /// Decodes the events of the given type emitted during the last execution of the transaction
+    ///
+    /// Available within `post_transaction`, `transaction_invariant_check` and
+    /// `transaction_error_handler` hooks.
+    fn events<T>(&self) -> Result<Vec<T>, FuzzingError>
+    where
+        T: Discriminator + borsh::BorshDeserialize,
+    {
+        get_transaction_result().events::<T>()
+    }
+
     /// Executes the transaction without lifecycle hooks
     ///
     /// This is a simplified version that only:
     /// - Creates transaction instructions
+    /// - Fails the transaction as already processed if it is a disallowed duplicate
     /// - Takes account snapshots before and after execution
+    /// - Runs authorization attacks if enabled
     /// - Processes the transaction
     /// - Records statistics if enabled
     ///
     /// It does NOT run pre/post hooks or invariant checks.
     fn execute_no_hooks(&mut self, client: &mut impl FuzzClient) -> Result<(), TransactionError> {
+        CrashReport::set_current_transaction(self.get_transaction_name());
+
         let instructions = self.create_transaction(client);
 
+        // The transaction identical to an already executed one fails as already processed
+        // before its accounts are captured, if the duplicate transactions are not allowed
+        if is_duplicate_transaction(&instructions) {
+            return Err(self.reject_duplicate_transaction(client, &instructions, false));
+        }
+
         let fuzzing_metrics = std::env::var("FUZZING_METRICS");
 
         // If stats are enabled, use the stats logger
         if fuzzing_metrics.is_ok() {
             let mut stats_logger = FuzzingStatistics::new();
 
-            // Record transaction invocation
+            // Record transaction invocation and the weight it was selected with
             stats_logger.increase_invoked(self.get_transaction_name());
+            if let Some(weight) = selection_weight(&self.get_transaction_name()) {
+                stats_logger.set_weight(self.get_transaction_name(), weight);
+            }
 
             // Take snapshot of accounts before execution
             self.set_snapshot_before(client);
 
-            // Execute the transaction
-            let tx_result = client.process_instructions(&instructions);
+            // Run authorization attacks if enabled
+            self.attack_authorization(client, &instructions);
+
+            // Execute the transaction, take snapshot of accounts after the successful
+            // execution and record the transaction in the replay log
+            let tx_result = self.process_transaction(client, &instructions, false);
+
+            // Record the consumed compute units
+            stats_logger.record_compute_units(
+                self.get_transaction_name(),
+                tx_result.compute_units_consumed(),
+            );
 
-            match tx_result {
+            match tx_result.into_result() {
                 Ok(_) => {
                     // Record successful execution
                     stats_logger.increase_successful(self.get_transaction_name());
 
                     // Output statistics
                     stats_logger.output_serialized();
-
-                    // Take snapshot of accounts after execution
-                    self.set_snapshot_after(client);
                 }
-                Err(_e) => {
+                Err(e) => {
                     // Record transaction failure
-                    stats_logger.increase_failed(self.get_transaction_name());
+                    stats_logger.increase_failed(
+                        self.get_transaction_name(),
+                        error_kind(&e, &instructions),
+                    );
                     stats_logger.output_serialized();
                 }
             }
@@ -183,18 +292,13 @@ pub trait TransactionMethods:
             // Take snapshot of accounts before execution
             self.set_snapshot_before(client);
 
-            // Execute the transaction
-            let tx_result = client.process_instructions(&instructions);
+            // Run authorization attacks if enabled
+            self.attack_authorization(client, &instructions);
 
-            match tx_result {
-                Ok(_) => {
-                    // Take snapshot of accounts after execution
-                    self.set_snapshot_after(client);
-                }
-                Err(_e) => {
-                    // No error handling in no_hooks mode
-                }
-            }
+            // Execute the transaction, take snapshot of accounts after the successful
+            // execution and record the transaction in the replay log
+            // No error handling in no_hooks mode
+            self.process_transaction(client, &instructions, false);
         }
 
         Ok(())
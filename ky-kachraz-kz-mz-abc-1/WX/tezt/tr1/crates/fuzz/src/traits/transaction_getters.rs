@@ -1,5 +1,7 @@
 use super::TransactionHooks;
+use crate::authorization_attacks::AttackTarget;
 use crate::traits::FuzzClient;
+use crate::trident_accounts::SnapshotAccount;
 use solana_sdk::instruction::AccountMeta;
 
 #[doc(hidden)]
@@ -8,6 +10,12 @@ pub trait TransactionGetters: TransactionHooks {
     /// Get transaction name
     fn get_transaction_name(&self) -> String;
 
+    #[doc(hidden)]
+    /// Get transaction name without the transaction instance
+    fn transaction_name() -> String
+    where
+        Self: Sized;
+
     #[doc(hidden)]
     /// Get instruction discriminators
     fn get_instruction_discriminators(&self) -> Vec<Vec<u8>>;
@@ -22,4 +30,20 @@ pub trait TransactionGetters: TransactionHooks {
     #[doc(hidden)]
     /// Get instruction accounts
     fn get_instruction_accounts(&mut self, client: &mut impl FuzzClient) -> Vec<Vec<AccountMeta>>;
+
+    #[doc(hidden)]
+    /// Rust code constructing the transaction with the same instruction data and account ids
+    fn to_rust_code(&self) -> String;
+
+    #[doc(hidden)]
+    /// Get authorization attacks applicable to the accounts of the instructions
+    fn get_instruction_attack_targets(&self) -> Vec<Vec<AttackTarget>> {
+        Vec::new()
+    }
+
+    #[doc(hidden)]
+    /// Get snapshots of the instruction accounts before and after the successful execution
+    fn get_account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+        Vec::new()
+    }
 }
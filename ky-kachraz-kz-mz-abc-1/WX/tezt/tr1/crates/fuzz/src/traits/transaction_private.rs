@@ -1,9 +1,20 @@
 use solana_sdk::instruction::Instruction;
+use solana_sdk::transaction::TransactionError;
 
 use super::TransactionHooks;
 use super::TransactionMethods;
 
+use crate::accounts_storage::malicious_account::{
+    find_malicious_account, forget_restored_malicious_accounts, restore_malicious_accounts,
+};
+use crate::authorization_attacks::{authorization_attacks_enabled, run_authorization_attacks};
+use crate::error::FuzzingError;
+use crate::fuzz_stats::error_kind;
+use crate::fuzzing::FuzzingStatistics;
+use crate::replay_log::{record_invariant_failure, record_transaction, TransactionOutcome};
 use crate::traits::FuzzClient;
+use crate::transaction_result::{set_transaction_result, TransactionResult};
+use crate::transaction_weights::selection_weight;
 
 /// Private trait that provides internal implementation details for transaction processing
 ///
@@ -18,10 +29,47 @@ pub(crate) trait TransactionPrivateMethods: TransactionHooks + std::fmt::Debug {
     /// - Instruction-specific data/parameters
     /// - Account metadata needed for the instructions
     fn create_transaction(&mut self, client: &mut impl FuzzClient) -> Vec<Instruction>;
+
+    /// Executes the authorization attacks on the instructions if they are enabled
+    ///
+    /// Every attack replaces or weakens one account of the instructions and panics
+    /// if the attacked transaction still succeeds.
+    fn attack_authorization(&self, client: &mut impl FuzzClient, instructions: &[Instruction]);
+
+    /// Fails if the successfully processed instructions were given a malicious account
+    fn check_malicious_accounts(&self, instructions: &[Instruction]) -> Result<(), FuzzingError>;
+
+    /// Processes the instructions and takes the snapshot of accounts after the successful execution
+    ///
+    /// The transaction is recorded in the replay log together with the differences
+    /// of the account snapshots if the replay log is recorded.
+    fn process_transaction(
+        &mut self,
+        client: &mut impl FuzzClient,
+        instructions: &[Instruction],
+        hooks: bool,
+    ) -> TransactionResult;
+
+    /// Fails the duplicate transaction as already processed without executing it
+    ///
+    /// The malicious accounts forged for the transaction are restored, the failure is
+    /// recorded in the replay log and in the statistics if they are enabled.
+    fn reject_duplicate_transaction(
+        &self,
+        client: &mut impl FuzzClient,
+        instructions: &[Instruction],
+        hooks: bool,
+    ) -> TransactionError;
+
+    /// Reports the failed invariant check performed after the transaction and panics
+    fn invariant_check_failed(&self, error: FuzzingError) -> !;
 }
 
 impl<T: TransactionMethods> TransactionPrivateMethods for T {
     fn create_transaction(&mut self, client: &mut impl FuzzClient) -> Vec<Instruction> {
+        // the malicious accounts of the previous transactions are no longer reported
+        forget_restored_malicious_accounts();
+
         // Retrieve instruction discriminators (identifiers for different instruction types)
         let discriminators = self.get_instruction_discriminators();
 
@@ -66,4 +114,91 @@ impl<T: TransactionMethods> TransactionPrivateMethods for T {
             })
             .collect()
     }
+
+    fn attack_authorization(&self, client: &mut impl FuzzClient, instructions: &[Instruction]) {
+        if authorization_attacks_enabled() {
+            run_authorization_attacks(client, self, instructions);
+        }
+    }
+
+    fn process_transaction(
+        &mut self,
+        client: &mut impl FuzzClient,
+        instructions: &[Instruction],
+        hooks: bool,
+    ) -> TransactionResult {
+        let tx_result = client.process_instructions(instructions);
+        set_transaction_result(&tx_result);
+
+        let outcome = match tx_result.result() {
+            Ok(_) => {
+                self.set_snapshot_after(client);
+                TransactionOutcome::Success
+            }
+            Err(e) => TransactionOutcome::Failed(e.to_string()),
+        };
+
+        // the malicious accounts are forged only for the duration of the transaction
+        restore_malicious_accounts(client);
+
+        record_transaction(self, instructions, hooks, outcome);
+
+        tx_result
+    }
+
+    fn reject_duplicate_transaction(
+        &self,
+        client: &mut impl FuzzClient,
+        instructions: &[Instruction],
+        hooks: bool,
+    ) -> TransactionError {
+        let error = TransactionError::AlreadyProcessed;
+        set_transaction_result(&TransactionResult::not_executed(error.clone()));
+
+        // the malicious accounts are forged when the transaction is built
+        restore_malicious_accounts(client);
+
+        record_transaction(
+            self,
+            instructions,
+            hooks,
+            TransactionOutcome::Failed(error.to_string()),
+        );
+
+        if std::env::var("FUZZING_METRICS").is_ok() {
+            let mut stats_logger = FuzzingStatistics::new();
+            stats_logger.increase_invoked(self.get_transaction_name());
+            if let Some(weight) = selection_weight(&self.get_transaction_name()) {
+                stats_logger.set_weight(self.get_transaction_name(), weight);
+            }
+            stats_logger.increase_failed(
+                self.get_transaction_name(),
+                error_kind(&error, instructions),
+            );
+            stats_logger.output_serialized();
+        }
+
+        error
+    }
+
+    fn invariant_check_failed(&self, error: FuzzingError) -> ! {
+        record_invariant_failure(&error.to_string());
+
+        eprintln!(
+            "\x1b[31mCRASH DETECTED!\x1b[0m Custom check after the {} transaction did not pass!",
+            self.get_transaction_name()
+        );
+        panic!("{}", error)
+    }
+
+    fn check_malicious_accounts(&self, instructions: &[Instruction]) -> Result<(), FuzzingError> {
+        match find_malicious_account(instructions) {
+            Some(account) => Err(FuzzingError::with_message(&format!(
+                "Malicious account accepted! The {} transaction succeeded with {}",
+                self.get_transaction_name(),
+                account
+            ))),
+            None => Ok(()),
+        }
+    }
 }
@@ -14,6 +14,11 @@ pub trait TransactionSelector<T> {
         fuzz_accounts: &mut T,
     ) -> Result<(), FuzzingError>;
 
+    // Select random Transaction with the probability proportional to its weight
+    fn arbitrary_weighted(fuzzer_data: &mut FuzzerData) -> arbitrary::Result<Self>
+    where
+        Self: Sized;
+
     // Select random Transaction and execute it
     fn select_n_execute(
         fuzzer_data: &mut FuzzerData,
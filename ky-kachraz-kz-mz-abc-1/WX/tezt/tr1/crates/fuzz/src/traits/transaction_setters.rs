@@ -18,4 +18,12 @@ pub trait TransactionSetters: TransactionHooks {
         client: &mut impl FuzzClient,
         fuzz_accounts: &mut Self::IxAccounts,
     );
+
+    #[doc(hidden)]
+    /// Set instruction accounts for the transaction, keeping the instruction data untouched
+    fn set_instruction_accounts(
+        &mut self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut Self::IxAccounts,
+    );
 }
@@ -0,0 +1,376 @@
+use std::cell::RefCell;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_sdk::inner_instruction::InnerInstructionsList;
+use solana_sdk::transaction::TransactionError;
+use solana_sdk::transaction_context::TransactionReturnData;
+
+use crate::error::FuzzingError;
+use crate::traits::Discriminator;
+
+/// Prefix of the log lines emitted by `sol_log_data`, used by the Anchor `emit!` macro.
+const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Tag prepended to the self-CPI instruction data emitted by the Anchor `emit_cpi!` macro.
+const EVENT_IX_TAG_LE: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+thread_local! {
+    /// Result of the transaction currently processed by `TransactionMethods::execute`.
+    static TRANSACTION_RESULT: RefCell<Option<TransactionResult>> = const { RefCell::new(None) };
+}
+
+/// Result of the processed transaction together with the program logs, return data,
+/// inner instructions and consumed compute units.
+#[derive(Debug, Clone)]
+pub struct TransactionResult {
+    result: Result<(), TransactionError>,
+    logs: Vec<String>,
+    return_data: Option<TransactionReturnData>,
+    inner_instructions: InnerInstructionsList,
+    compute_units_consumed: u64,
+}
+
+impl TransactionResult {
+    pub fn new(
+        result: Result<(), TransactionError>,
+        logs: Vec<String>,
+        return_data: Option<TransactionReturnData>,
+        inner_instructions: InnerInstructionsList,
+        compute_units_consumed: u64,
+    ) -> Self {
+        Self {
+            result,
+            logs,
+            return_data,
+            inner_instructions,
+            compute_units_consumed,
+        }
+    }
+    /// Result of the transaction which was rejected before its execution.
+    pub fn not_executed(error: TransactionError) -> Self {
+        Self::new(Err(error), Vec::new(), None, Vec::new(), 0)
+    }
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+    pub fn is_err(&self) -> bool {
+        self.result.is_err()
+    }
+    pub fn result(&self) -> &Result<(), TransactionError> {
+        &self.result
+    }
+    pub fn into_result(self) -> Result<(), TransactionError> {
+        self.result
+    }
+    /// Program logs, truncated to the first 10 000 bytes.
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+    /// Data returned by the last program which set the return data.
+    pub fn return_data(&self) -> Option<&TransactionReturnData> {
+        self.return_data.as_ref()
+    }
+    /// Instructions invoked by the programs through CPI, grouped by the top-level instructions.
+    pub fn inner_instructions(&self) -> &InnerInstructionsList {
+        &self.inner_instructions
+    }
+    pub fn compute_units_consumed(&self) -> u64 {
+        self.compute_units_consumed
+    }
+    /// Compute units consumed by each top-level instruction, including the instructions it
+    /// invoked through CPI, parsed from the program logs.
+    ///
+    /// `None` if the program did not log the consumed compute units, e.g. builtin programs,
+    /// or if the logs were truncated. The instructions invoked after the logs were truncated
+    /// are missing.
+    pub fn instruction_compute_units(&self) -> Vec<Option<u64>> {
+        let mut instructions = Vec::new();
+        let mut depth = 0usize;
+
+        for log in &self.logs {
+            let words: Vec<&str> = log.split_whitespace().collect();
+            // skip the messages logged by the programs, e.g. `Program log: ...`
+            if words.len() < 3 || words[0] != "Program" || words[1].ends_with(':') {
+                continue;
+            }
+            match words[2] {
+                "invoke" => {
+                    depth += 1;
+                    if depth == 1 {
+                        instructions.push(None);
+                    }
+                }
+                "consumed" if depth == 1 => {
+                    if let (Some(last), Some(Ok(compute_units))) = (
+                        instructions.last_mut(),
+                        words.get(3).map(|units| units.parse::<u64>()),
+                    ) {
+                        *last = Some(compute_units);
+                    }
+                }
+                "success" | "failed:" => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        instructions
+    }
+    /// Decodes the events of the given type emitted during the transaction.
+    ///
+    /// Events emitted with `emit!` are read from the `Program data:` log lines in the order
+    /// they were logged, followed by the events emitted with `emit_cpi!`, which are read from
+    /// the inner instructions. Events logged after the logs were truncated are not returned.
+    ///
+    /// Fails if the data starts with the discriminator of the type but the rest of the
+    /// data cannot be deserialized.
+    pub fn events<T>(&self) -> Result<Vec<T>, FuzzingError>
+    where
+        T: Discriminator + borsh::BorshDeserialize,
+    {
+        let logged = self
+            .logs
+            .iter()
+            .filter_map(|log| log.strip_prefix(PROGRAM_DATA_LOG_PREFIX))
+            // data logged by other programs does not have to be a single base64 encoded slice
+            .filter_map(|data| STANDARD.decode(data).ok());
+
+        let invoked = self
+            .inner_instructions
+            .iter()
+            .flatten()
+            .filter_map(|inner| inner.instruction.data.strip_prefix(&EVENT_IX_TAG_LE))
+            .map(|data| data.to_vec());
+
+        logged
+            .chain(invoked)
+            .filter(|data| data.starts_with(T::DISCRIMINATOR))
+            .map(|data| {
+                let mut rest = &data[T::DISCRIMINATOR.len()..];
+                T::deserialize(&mut rest).map_err(|e| {
+                    FuzzingError::with_message(&format!(
+                        "Event cannot be deserialized into {}: {}",
+                        std::any::type_name::<T>(),
+                        e
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Remembers the result of the transaction processed by `TransactionMethods::execute`.
+pub(crate) fn set_transaction_result(result: &TransactionResult) {
+    TRANSACTION_RESULT.with(|current| *current.borrow_mut() = Some(result.clone()));
+}
+
+/// Returns the result of the transaction processed by `TransactionMethods::execute`.
+pub(crate) fn get_transaction_result() -> TransactionResult {
+    TRANSACTION_RESULT.with(|current| match &*current.borrow() {
+        Some(result) => result.clone(),
+        None => panic!("Transaction result is not set"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::inner_instruction::InnerInstruction;
+    use solana_sdk::instruction::{CompiledInstruction, InstructionError};
+
+    use super::*;
+
+    const PROGRAM: &str = "8bPSKGoWCdAW8Hu3S1hLHPpBv8BNwse4jDyaXNrj3jWB";
+    const OTHER_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+    #[derive(Debug, PartialEq, borsh::BorshDeserialize, borsh::BorshSerialize)]
+    struct Deposited {
+        amount: u64,
+    }
+
+    impl Discriminator for Deposited {
+        const DISCRIMINATOR: &'static [u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+    }
+
+    fn result(logs: &[String], inner_instructions: InnerInstructionsList) -> TransactionResult {
+        TransactionResult::new(Ok(()), logs.to_vec(), None, inner_instructions, 0)
+    }
+
+    fn event_data(amount: u64) -> Vec<u8> {
+        let mut data = Deposited::DISCRIMINATOR.to_vec();
+        data.extend(borsh::to_vec(&Deposited { amount }).unwrap());
+        data
+    }
+
+    fn data_log(data: &[u8]) -> String {
+        format!("{}{}", PROGRAM_DATA_LOG_PREFIX, STANDARD.encode(data))
+    }
+
+    fn inner_instruction(data: Vec<u8>) -> InnerInstruction {
+        InnerInstruction {
+            instruction: CompiledInstruction::new_from_raw_parts(0, data, vec![]),
+            stack_height: 2,
+        }
+    }
+
+    fn invoke(program: &str, depth: usize) -> String {
+        format!("Program {} invoke [{}]", program, depth)
+    }
+
+    fn consumed(program: &str, compute_units: u64) -> String {
+        format!(
+            "Program {} consumed {} of 200000 compute units",
+            program, compute_units
+        )
+    }
+
+    fn success(program: &str) -> String {
+        format!("Program {} success", program)
+    }
+
+    #[test]
+    fn test_instruction_compute_units() {
+        let logs = [
+            invoke(PROGRAM, 1),
+            "Program log: Instruction: Deposit".to_string(),
+            consumed(PROGRAM, 1500),
+            success(PROGRAM),
+            invoke(OTHER_PROGRAM, 1),
+            consumed(OTHER_PROGRAM, 700),
+            success(OTHER_PROGRAM),
+        ];
+        assert_eq!(
+            result(&logs, vec![]).instruction_compute_units(),
+            vec![Some(1500), Some(700)]
+        );
+    }
+
+    #[test]
+    fn test_instruction_compute_units_nested_invokes() {
+        // the compute units of the inner instructions are included in the top-level ones
+        let logs = [
+            invoke(PROGRAM, 1),
+            invoke(OTHER_PROGRAM, 2),
+            consumed(OTHER_PROGRAM, 400),
+            success(OTHER_PROGRAM),
+            invoke(SYSTEM_PROGRAM, 2),
+            success(SYSTEM_PROGRAM),
+            consumed(PROGRAM, 2400),
+            success(PROGRAM),
+            invoke(SYSTEM_PROGRAM, 1),
+            success(SYSTEM_PROGRAM),
+        ];
+        assert_eq!(
+            result(&logs, vec![]).instruction_compute_units(),
+            vec![Some(2400), None]
+        );
+    }
+
+    #[test]
+    fn test_instruction_compute_units_failed() {
+        let logs = [
+            invoke(PROGRAM, 1),
+            invoke(OTHER_PROGRAM, 2),
+            consumed(OTHER_PROGRAM, 300),
+            format!(
+                "Program {} failed: custom program error: 0x1",
+                OTHER_PROGRAM
+            ),
+            consumed(PROGRAM, 900),
+            format!("Program {} failed: custom program error: 0x1", PROGRAM),
+        ];
+        let result = TransactionResult::new(
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(1),
+            )),
+            logs.to_vec(),
+            None,
+            vec![],
+            900,
+        );
+        assert_eq!(result.instruction_compute_units(), vec![Some(900)]);
+    }
+
+    #[test]
+    fn test_instruction_compute_units_truncated_logs() {
+        // the instruction interrupted by the truncation has no compute units and the
+        // following instructions are missing
+        let logs = [
+            invoke(PROGRAM, 1),
+            consumed(PROGRAM, 1500),
+            success(PROGRAM),
+            invoke(PROGRAM, 1),
+            "Program log: Instruction: Deposit".to_string(),
+            "Log truncated".to_string(),
+        ];
+        assert_eq!(
+            result(&logs, vec![]).instruction_compute_units(),
+            vec![Some(1500), None]
+        );
+    }
+
+    #[test]
+    fn test_instruction_compute_units_ignore_program_messages() {
+        let logs = [
+            invoke(PROGRAM, 1),
+            "Program log: Program invoke consumed success".to_string(),
+            data_log(&event_data(1)),
+            format!("Program return: {} AQ==", PROGRAM),
+            consumed(PROGRAM, 1200),
+            success(PROGRAM),
+        ];
+        assert_eq!(
+            result(&logs, vec![]).instruction_compute_units(),
+            vec![Some(1200)]
+        );
+    }
+
+    #[test]
+    fn test_events() {
+        let logs = [
+            invoke(PROGRAM, 1),
+            data_log(&event_data(10)),
+            // data of other events and programs
+            data_log(&[8, 7, 6, 5, 4, 3, 2, 1, 0]),
+            format!("{}AQ== Ag==", PROGRAM_DATA_LOG_PREFIX),
+            data_log(&event_data(20)),
+            consumed(PROGRAM, 1200),
+            success(PROGRAM),
+        ];
+        let inner_instructions = vec![vec![
+            inner_instruction([EVENT_IX_TAG_LE.to_vec(), event_data(30)].concat()),
+            inner_instruction(event_data(40)),
+        ]];
+
+        let events = result(&logs, inner_instructions)
+            .events::<Deposited>()
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Deposited { amount: 10 },
+                Deposited { amount: 20 },
+                Deposited { amount: 30 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_truncated_logs() {
+        let logs = [
+            invoke(PROGRAM, 1),
+            data_log(&event_data(10)),
+            "Log truncated".to_string(),
+        ];
+        let events = result(&logs, vec![]).events::<Deposited>().unwrap();
+        assert_eq!(events, vec![Deposited { amount: 10 }]);
+    }
+
+    #[test]
+    fn test_events_not_deserializable() {
+        let mut data = event_data(10);
+        data.truncate(Deposited::DISCRIMINATOR.len() + 4);
+        let logs = [data_log(&data)];
+        assert!(result(&logs, vec![]).events::<Deposited>().is_err());
+    }
+}
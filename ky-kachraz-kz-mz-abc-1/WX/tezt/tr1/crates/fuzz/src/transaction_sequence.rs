@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use solana_sdk::hash::{hash, Hash};
+use solana_sdk::instruction::Instruction;
+
+use crate::types::FuzzerData;
+
+/// Whether identical transactions can be executed repeatedly within one fuzzing iteration,
+/// allowed unless disabled in the config.
+static ALLOW_DUPLICATE_TXS: OnceLock<bool> = OnceLock::new();
+
+/// Minimal and maximal number of the random transactions executed per fuzzing iteration.
+static TRANSACTIONS_BOUNDS: OnceLock<(Option<usize>, Option<usize>)> = OnceLock::new();
+
+thread_local! {
+    /// Hashes of the transactions executed during the fuzzing iteration.
+    static EXECUTED_TRANSACTIONS: RefCell<HashSet<Hash>> = RefCell::new(HashSet::new());
+}
+
+/// Allows identical transactions to be executed repeatedly within one fuzzing iteration.
+/// Only the first call has an effect, later calls are ignored.
+pub fn set_allow_duplicate_txs(allow: bool) {
+    let _ = ALLOW_DUPLICATE_TXS.set(allow);
+}
+
+/// Sets the minimal and maximal number of the random transactions executed per fuzzing
+/// iteration, `None` means the number is not bounded. Only the first call has an effect,
+/// later calls are ignored.
+pub fn set_transactions_bounds(min_transactions: Option<usize>, max_transactions: Option<usize>) {
+    if let (Some(min), Some(max)) = (min_transactions, max_transactions) {
+        assert!(
+            min <= max,
+            "min_transactions ({}) cannot be greater than max_transactions ({})",
+            min,
+            max
+        );
+    }
+    let _ = TRANSACTIONS_BOUNDS.set((min_transactions, max_transactions));
+}
+
+/// Returns the number of the random transactions executed in the fuzzing iteration,
+/// chosen from the fuzzer data within the configured bounds.
+pub fn random_transactions_count<T>(fuzzer_data: &mut FuzzerData) -> arbitrary::Result<usize>
+where
+    T: for<'a> arbitrary::Arbitrary<'a>,
+{
+    match TRANSACTIONS_BOUNDS.get().copied().unwrap_or_default() {
+        (min, Some(max)) => fuzzer_data.int_in_range(min.unwrap_or_default()..=max),
+        (Some(min), None) => Ok(fuzzer_data.arbitrary_len::<T>()?.max(min)),
+        (None, None) => fuzzer_data.arbitrary_len::<T>(),
+    }
+}
+
+/// Returns true if the identical transaction was already executed in the fuzzing iteration
+/// and the duplicate transactions are not allowed. Otherwise, remembers the transaction.
+pub(crate) fn is_duplicate_transaction(instructions: &[Instruction]) -> bool {
+    if ALLOW_DUPLICATE_TXS.get().copied().unwrap_or(true) {
+        return false;
+    }
+
+    let serialized = bincode::serialize(instructions).expect("Failed to serialize instructions");
+    EXECUTED_TRANSACTIONS.with(|executed| !executed.borrow_mut().insert(hash(&serialized)))
+}
+
+/// Forgets the transactions executed during the fuzzing iteration.
+pub(crate) fn clear_executed_transactions() {
+    EXECUTED_TRANSACTIONS.with(|executed| executed.borrow_mut().clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_transactions() {
+        set_allow_duplicate_txs(false);
+        let program_id = solana_sdk::pubkey::Pubkey::new_unique();
+        let deposit = [Instruction::new_with_bytes(program_id, &[1, 10], vec![])];
+        let withdraw = [Instruction::new_with_bytes(program_id, &[2, 10], vec![])];
+
+        assert!(!is_duplicate_transaction(&deposit));
+        assert!(!is_duplicate_transaction(&withdraw));
+        assert!(is_duplicate_transaction(&deposit));
+
+        // the next fuzzing iteration starts without executed transactions
+        clear_executed_transactions();
+        assert!(!is_duplicate_transaction(&deposit));
+    }
+}
@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::types::FuzzerData;
+
+/// Weights of the transactions overriding the `#[weight(N)]` attributes, keyed by the transaction name.
+static WEIGHT_OVERRIDES: OnceLock<HashMap<String, u32>> = OnceLock::new();
+
+thread_local! {
+    /// Weights the transactions were last selected with, shown in the fuzzing statistics.
+    static SELECTION_WEIGHTS: RefCell<HashMap<String, u32>> = RefCell::new(HashMap::new());
+}
+
+/// Sets the weights overriding the `#[weight(N)]` attributes of the `TransactionSelector` variants.
+/// Only the first call has an effect, later calls are ignored.
+pub fn set_transaction_weights(weights: HashMap<String, u32>) {
+    let _ = WEIGHT_OVERRIDES.set(weights);
+}
+
+/// Returns the weights the transactions of a `TransactionSelector` are selected with, given
+/// their names and the weights from the `#[weight(N)]` attributes. The weights from the config
+/// take precedence, a warning is printed for the overridden names which match no transaction.
+///
+/// The weights are computed once per selector, so this is called only on its first selection.
+pub fn transaction_weights(transactions: &[(String, u32)]) -> Vec<u32> {
+    let overrides = WEIGHT_OVERRIDES.get().cloned().unwrap_or_default();
+    for name in unknown_transactions(&overrides, transactions) {
+        eprintln!(
+            "\x1b[33mWarning\x1b[0m The weight of {} is overridden in Trident.toml, but no such transaction is selected by the TransactionSelector",
+            name
+        );
+    }
+
+    let weights = resolve_weights(&overrides, transactions);
+    SELECTION_WEIGHTS.with(|selection_weights| {
+        let mut selection_weights = selection_weights.borrow_mut();
+        for ((name, _), weight) in transactions.iter().zip(&weights) {
+            selection_weights.insert(name.clone(), *weight);
+        }
+    });
+    weights
+}
+
+/// Returns the weights of the transactions, overridden by their names.
+fn resolve_weights(overrides: &HashMap<String, u32>, transactions: &[(String, u32)]) -> Vec<u32> {
+    transactions
+        .iter()
+        .map(|(name, weight)| overrides.get(name).copied().unwrap_or(*weight))
+        .collect()
+}
+
+/// Returns the sorted names of the overrides which do not match any transaction.
+fn unknown_transactions<'a>(
+    overrides: &'a HashMap<String, u32>,
+    transactions: &[(String, u32)],
+) -> Vec<&'a str> {
+    let mut unknown: Vec<_> = overrides
+        .keys()
+        .filter(|name| {
+            !transactions
+                .iter()
+                .any(|(transaction, _)| transaction == *name)
+        })
+        .map(String::as_str)
+        .collect();
+    unknown.sort_unstable();
+    unknown
+}
+
+/// Returns the weight the transaction is selected with, `None` if it is not a variant
+/// of the `TransactionSelector` or no transaction was selected yet.
+pub(crate) fn selection_weight(transaction_name: &str) -> Option<u32> {
+    SELECTION_WEIGHTS.with(|weights| weights.borrow().get(transaction_name).copied())
+}
+
+/// Selects an index with the probability proportional to its weight.
+/// Fails if all weights are zero.
+pub fn select_weighted(fuzzer_data: &mut FuzzerData, weights: &[u32]) -> arbitrary::Result<usize> {
+    let total_weight: u64 = weights.iter().map(|weight| *weight as u64).sum();
+    if total_weight == 0 {
+        return Err(arbitrary::Error::IncorrectFormat);
+    }
+
+    let mut choice = fuzzer_data.int_in_range(0..=total_weight - 1)?;
+    for (index, weight) in weights.iter().enumerate() {
+        if choice < *weight as u64 {
+            return Ok(index);
+        }
+        choice -= *weight as u64;
+    }
+    unreachable!("the choice is always lower than the total weight")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transactions() -> Vec<(String, u32)> {
+        vec![
+            ("Deposit".to_string(), 3),
+            ("Withdraw".to_string(), 1),
+            ("Close".to_string(), 0),
+        ]
+    }
+
+    /// Selects an index for every possible single byte of the fuzzer data.
+    fn selection_counts(weights: &[u32]) -> Vec<usize> {
+        let mut counts = vec![0; weights.len()];
+        for byte in 0..=u8::MAX {
+            let data = [byte];
+            let mut fuzzer_data = FuzzerData::new(&data);
+            counts[select_weighted(&mut fuzzer_data, weights).unwrap()] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_select_weighted_proportional() {
+        assert_eq!(selection_counts(&[3, 1]), vec![192, 64]);
+        assert_eq!(selection_counts(&[1, 1, 2]), vec![64, 64, 128]);
+    }
+
+    #[test]
+    fn test_select_weighted_zero_weight() {
+        let counts = selection_counts(&[0, 2, 0, 2]);
+        assert_eq!(counts[0], 0);
+        assert_eq!(counts[2], 0);
+        assert_eq!(counts[1] + counts[3], 256);
+    }
+
+    #[test]
+    fn test_select_weighted_all_zero() {
+        let mut fuzzer_data = FuzzerData::new(&[0, 1, 2, 3]);
+        assert!(select_weighted(&mut fuzzer_data, &[0, 0]).is_err());
+        assert!(select_weighted(&mut fuzzer_data, &[]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_weights() {
+        let overrides = HashMap::from([("Withdraw".to_string(), 5), ("Close".to_string(), 2)]);
+        assert_eq!(resolve_weights(&overrides, &transactions()), vec![3, 5, 2]);
+        assert_eq!(
+            resolve_weights(&HashMap::new(), &transactions()),
+            vec![3, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_unknown_transactions() {
+        let overrides = HashMap::from([
+            ("Withdraw".to_string(), 5),
+            ("Withdrawal".to_string(), 2),
+            ("Burn".to_string(), 1),
+        ]);
+        assert_eq!(
+            unknown_transactions(&overrides, &transactions()),
+            vec!["Burn", "Withdrawal"]
+        );
+    }
+}
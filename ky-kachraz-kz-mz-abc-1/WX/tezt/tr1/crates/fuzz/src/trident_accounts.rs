@@ -8,11 +8,16 @@ use solana_sdk::clock::Epoch;
 use solana_sdk::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
 
+use crate::accounts_storage::malicious_account::AccountForgery;
+use crate::error::FuzzingError;
+use crate::traits::Discriminator;
 use crate::{fuzzing::FuzzClient, types::AccountId};
 
 #[derive(Clone)]
 pub struct TridentAccount {
     pub account_id: AccountId,
+    /// Forgery of the account if the field has the `malicious` constraint.
+    pub forgery: Option<AccountForgery>,
     account_meta: Option<AccountMeta>,
     snapshot_before: Option<SnapshotAccount>,
     snapshot_after: Option<SnapshotAccount>,
@@ -24,6 +29,7 @@ impl<'a> Arbitrary<'a> for TridentAccount {
         u.fill_buffer(&mut buf)?;
         Ok(Self {
             account_id: AccountId::from_le_bytes(buf),
+            forgery: AccountForgery::from_byte(u.arbitrary()?),
             account_meta: None,
             snapshot_before: None,
             snapshot_after: None,
@@ -31,12 +37,36 @@ impl<'a> Arbitrary<'a> for TridentAccount {
     }
     #[inline]
     fn size_hint(_depth: usize) -> (usize, Option<usize>) {
-        let n = std::mem::size_of::<AccountId>();
+        let n = std::mem::size_of::<AccountId>() + std::mem::size_of::<u8>();
         (n, Some(n))
     }
 }
 
 impl TridentAccount {
+    pub fn new(account_id: AccountId) -> Self {
+        Self {
+            account_id,
+            forgery: None,
+            account_meta: None,
+            snapshot_before: None,
+            snapshot_after: None,
+        }
+    }
+    /// Forges the account if the field has the `malicious` constraint.
+    pub fn with_forgery(mut self, forgery: AccountForgery) -> Self {
+        self.forgery = Some(forgery);
+        self
+    }
+    #[doc(hidden)]
+    pub fn to_rust_code(&self) -> String {
+        match self.forgery {
+            Some(forgery) => format!(
+                "TridentAccount::new({}).with_forgery(AccountForgery::{:?})",
+                self.account_id, forgery
+            ),
+            None => format!("TridentAccount::new({})", self.account_id),
+        }
+    }
     pub fn set_account_meta(&mut self, address: Pubkey, is_signer: bool, is_writable: bool) {
         if is_writable {
             self.account_meta = Some(AccountMeta::new(address, is_signer));
@@ -52,6 +82,8 @@ impl TridentAccount {
                 address: account_meta.pubkey,
                 account,
             });
+            // the snapshot after the previous execution does not belong to this one
+            self.snapshot_after = None;
         }
     }
     #[doc(hidden)]
@@ -75,6 +107,13 @@ impl TridentAccount {
     pub fn is_account_meta_set(&self) -> bool {
         self.account_meta.is_some()
     }
+    /// Snapshots before and after the execution, if the execution succeeded
+    #[doc(hidden)]
+    pub fn get_snapshots(&self) -> Option<(&SnapshotAccount, &SnapshotAccount)> {
+        self.snapshot_before
+            .as_ref()
+            .zip(self.snapshot_after.as_ref())
+    }
     pub fn get_snapshot_before(&self) -> &SnapshotAccount {
         match &self.snapshot_before {
             Some(snapshot) => snapshot,
@@ -171,4 +210,32 @@ impl SnapshotAccount {
     pub fn rent_epoch(&self) -> Epoch {
         self.account.rent_epoch()
     }
+    /// Deserializes the account data into the program account type.
+    ///
+    /// Fails if the data does not start with the discriminator of the type
+    /// or if the rest of the data cannot be deserialized.
+    pub fn deserialize<T>(&self) -> std::result::Result<T, FuzzingError>
+    where
+        T: Discriminator + borsh::BorshDeserialize,
+    {
+        let data = self.account.data();
+        if !data.starts_with(T::DISCRIMINATOR) {
+            return Err(FuzzingError::with_message(&format!(
+                "Account {} does not have the discriminator of {}",
+                self.address,
+                std::any::type_name::<T>()
+            )));
+        }
+
+        // Trailing bytes are allowed, as accounts are usually allocated with extra space
+        let mut rest = &data[T::DISCRIMINATOR.len()..];
+        T::deserialize(&mut rest).map_err(|e| {
+            FuzzingError::with_message(&format!(
+                "Account {} cannot be deserialized into {}: {}",
+                self.address,
+                std::any::type_name::<T>(),
+                e
+            ))
+        })
+    }
 }
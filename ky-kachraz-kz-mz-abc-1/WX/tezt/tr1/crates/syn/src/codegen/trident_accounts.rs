@@ -90,9 +90,27 @@ impl ToTokens for TridentAccountsStruct {
                             }
                         };
 
+                        let malicious_substitution = if f.constraints.malicious {
+                            quote! {
+                                storage_accounts
+                                    .#storage_ident
+                                    .forge_malicious_account(
+                                        self.#field_name.forgery,
+                                        self.#field_name.account_id,
+                                        client,
+                                        account,
+                                        #is_mutable,
+                                        stringify!(#field_name),
+                                    );
+                            }
+                        } else {
+                            quote! {}
+                        };
+
                         quote! {
                             let #field_name = {
                                 let account = #account_resolution;
+                                #malicious_substitution
                                 self.#field_name.set_address(account);
 
                                 if #is_signer {
@@ -155,6 +173,35 @@ impl ToTokens for TridentAccountsStruct {
             }
         });
 
+        // Generate the authorization attacks applicable to each account
+        let account_attacks_fields = self.fields.iter().map(|field| match field {
+            TridentAccountField::Field(f) => {
+                let field_name = &f.ident;
+                let mut attacks = Vec::new();
+                if !f.constraints.skip_attacks {
+                    if f.constraints.signer {
+                        attacks.push(quote!(AccountAttack::UnrelatedSigner));
+                        attacks.push(quote!(AccountAttack::MissingSignature));
+                    }
+                    if f.constraints.address.is_some() || f.constraints.seeds.is_some() {
+                        attacks.push(quote!(AccountAttack::AccountCopy));
+                        attacks.push(quote!(AccountAttack::AttackerOwnedAccount));
+                    }
+                }
+                quote! {
+                    targets.push(AttackTarget::new(stringify!(#field_name), vec![#(#attacks),*]));
+                }
+            }
+            TridentAccountField::CompositeField(f) => {
+                let field_name = &f.ident;
+                quote! {
+                    targets.extend(self.#field_name.account_attacks());
+                }
+            }
+        });
+
+        let field_idents: Vec<_> = self.fields.iter().map(|field| field.ident()).collect();
+
         let snapshot_fields: Vec<_> = self
             .fields
             .iter()
@@ -165,6 +212,29 @@ impl ToTokens for TridentAccountsStruct {
             .map(|field| field.ident())
             .collect();
 
+        // Snapshots of the accounts, the composite fields collect the snapshots of their accounts
+        let account_snapshots_fields = self
+            .fields
+            .iter()
+            .filter(|field| match field {
+                TridentAccountField::Field(f) => !f.constraints.skip_snapshot,
+                TridentAccountField::CompositeField(f) => !f.constraints.skip_snapshot,
+            })
+            .map(|field| match field {
+                TridentAccountField::Field(f) => {
+                    let field_name = &f.ident;
+                    quote! {
+                        snapshots.extend(self.#field_name.get_snapshots());
+                    }
+                }
+                TridentAccountField::CompositeField(f) => {
+                    let field_name = &f.ident;
+                    quote! {
+                        snapshots.extend(self.#field_name.account_snapshots());
+                    }
+                }
+            });
+
         let expanded = quote! {
             impl AccountsMethods for #name {
                 type IxAccounts = #storage_type;
@@ -210,6 +280,25 @@ impl ToTokens for TridentAccountsStruct {
                         }
                     )*
                 }
+
+                fn to_rust_code(&self) -> String {
+                    let fields: Vec<String> = vec![
+                        #(format!("{}: {}", stringify!(#field_idents), self.#field_idents.to_rust_code())),*
+                    ];
+                    format!("{} {{ {} }}", stringify!(#name), fields.join(", "))
+                }
+
+                fn account_attacks(&self) -> Vec<AttackTarget> {
+                    let mut targets = Vec::new();
+                    #(#account_attacks_fields)*
+                    targets
+                }
+
+                fn account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+                    let mut snapshots = Vec::new();
+                    #(#account_snapshots_fields)*
+                    snapshots
+                }
             }
         };
 
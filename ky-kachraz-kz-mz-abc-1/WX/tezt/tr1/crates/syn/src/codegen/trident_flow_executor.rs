@@ -8,6 +8,7 @@ impl ToTokens for TridentFlowExecutorImpl {
         let type_name = &self.type_name;
         let impl_items = &self.impl_block;
         let methods = &self.flow_methods;
+        let invariants = &self.invariant_methods;
         let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
 
         // Generate init call if present
@@ -19,24 +20,108 @@ impl ToTokens for TridentFlowExecutorImpl {
             quote! {}
         };
 
+        // The `#[invariant]` functions are checked after every successfully executed transaction,
+        // on the live accounts once the transaction no longer borrows them
+        let set_invariants = if invariants.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                set_flow_invariants(&self.client, |client, accounts: &FuzzAccounts| {
+                    #(
+                        Self::#invariants(client, accounts).inspect_err(|_| {
+                            eprintln!("Invariant {} did not pass!", stringify!(#invariants));
+                        })?;
+                    )*
+                    Ok(())
+                });
+            }
+        };
+
         // Use default_random_flows if there are no flow methods
         let execute_impl = if methods.is_empty() {
             quote! {
                 // No flow methods or all are ignored, use default implementation
+                ReplayLog::flow("default_random_transactions");
                 self.default_random_transactions(fuzzer_data, &mut accounts)?;
             }
         } else {
             let random_tail = if self.args.random_tail {
                 quote! {
+                    ReplayLog::flow("default_random_transactions");
                     self.default_random_transactions(fuzzer_data, &mut accounts)?;
                 }
             } else {
                 quote! {}
             };
 
+            let flows = methods
+                .iter()
+                .map(|method| &method.ident)
+                .collect::<Vec<_>>();
+
+            let execute_flows = if let Some(flow_calls) = self.args.flow_calls {
+                // Each call selects a flow with the probability proportional to its weight,
+                // the parser checks the sum of the weights does not overflow
+                let mut total_weight = 0u32;
+                let weight_ranges = methods.iter().map(|method| {
+                    let start = total_weight;
+                    total_weight += method.weight;
+                    let end = total_weight - 1;
+                    quote! { #start..=#end }
+                });
+                let weight_ranges = weight_ranges.collect::<Vec<_>>();
+                let max_choice = total_weight - 1;
+
+                quote! {
+                    // Execute the flows selected by their weights
+                    let flow_calls = fuzzer_data.int_in_range(1..=#flow_calls)?;
+                    for _ in 0..flow_calls {
+                        match fuzzer_data.int_in_range(0..=#max_choice)? {
+                            #(
+                                #weight_ranges => {
+                                    ReplayLog::flow(stringify!(#flows));
+                                    self.#flows(fuzzer_data, &mut accounts)?;
+                                }
+                            )*
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+            } else if self.args.shuffle {
+                let indices = 0..flows.len();
+                let flows_count = flows.len();
+
+                quote! {
+                    // Execute all flows in the order shuffled by the fuzzer data
+                    let mut order: Vec<usize> = (0..#flows_count).collect();
+                    for i in (1..order.len()).rev() {
+                        let j = fuzzer_data.int_in_range(0..=i)?;
+                        order.swap(i, j);
+                    }
+                    for flow in order {
+                        match flow {
+                            #(
+                                #indices => {
+                                    ReplayLog::flow(stringify!(#flows));
+                                    self.#flows(fuzzer_data, &mut accounts)?;
+                                }
+                            )*
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    // Execute all defined flow methods
+                    #(
+                        ReplayLog::flow(stringify!(#flows));
+                        self.#flows(fuzzer_data, &mut accounts)?;
+                    )*
+                }
+            };
+
             quote! {
-                // Execute all defined flow methods
-                #(self.#methods(fuzzer_data, &mut accounts)?;)*
+                #execute_flows
 
                 // Optional random tail transactions
                 #random_tail
@@ -53,11 +138,62 @@ impl ToTokens for TridentFlowExecutorImpl {
                     &mut self,
                     fuzzer_data: &mut FuzzerData,
                 ) -> std::result::Result<(), FuzzingError> {
+                    // the storages are named in the order they are created
+                    reset_storage_names();
+                    let mut accounts = FuzzAccounts::default();
+
+                    // the init method is executed only in the first iteration, the following
+                    // iterations restore the state of the client captured right after it
+                    thread_local! {
+                        static INIT_SNAPSHOT: std::cell::RefCell<Option<ClientSnapshot>> =
+                            const { std::cell::RefCell::new(None) };
+                    }
+                    let init_snapshot = INIT_SNAPSHOT.with(|snapshot| {
+                        snapshot
+                            .borrow_mut()
+                            .take()
+                            .filter(|snapshot| snapshot.is_taken_by(&self.client))
+                    });
+                    let init_snapshot = match init_snapshot {
+                        Some(snapshot) => {
+                            self.client.restore(&snapshot);
+                            snapshot
+                        }
+                        None => {
+                            #init_call
+                            self.client.snapshot()
+                        }
+                    };
+                    INIT_SNAPSHOT.with(|snapshot| *snapshot.borrow_mut() = Some(init_snapshot));
+
+                    #set_invariants
+                    let result = (|| -> std::result::Result<(), FuzzingError> {
+                        #execute_impl
+                        Ok(())
+                    })();
+
+                    // the invariants are checked after the last transaction of the flows
+                    check_flow_invariants(&mut self.client, &accounts);
+                    result
+                }
+
+                /// Executes the init method followed by the given transactions,
+                /// used by the regression tests generated from crash files.
+                #[allow(dead_code)]
+                pub fn reproduce<F>(&mut self, transactions: F)
+                where
+                    F: FnOnce(&mut Self, &mut FuzzAccounts),
+                {
+                    // the storages are named in the order they are created
+                    reset_storage_names();
                     let mut accounts = FuzzAccounts::default();
 
                     #init_call
-                    #execute_impl
-                    Ok(())
+                    #set_invariants
+                    CrashReport::assert_no_panic(|| {
+                        transactions(self, &mut accounts);
+                        check_flow_invariants(&mut self.client, &accounts);
+                    });
                 }
 
                 fn default_random_transactions(
@@ -65,29 +201,59 @@ impl ToTokens for TridentFlowExecutorImpl {
                     fuzzer_data: &mut FuzzerData,
                     accounts: &mut FuzzAccounts,
                 ) -> std::result::Result<(), FuzzingError> {
-                    let mut transactions = <Vec<FuzzTransactions>>::arbitrary(fuzzer_data)?;
+                    // the transactions are selected with the probability proportional to their weights
+                    let transactions_count =
+                        random_transactions_count::<FuzzTransactions>(fuzzer_data)?;
+                    let mut transactions = (0..transactions_count)
+                        .map(|_| FuzzTransactions::arbitrary_weighted(fuzzer_data))
+                        .collect::<arbitrary::Result<Vec<_>>>()?;
                     for transaction in transactions.iter_mut() {
-                        transaction.transaction_selector(&mut self.client, accounts)?
+                        transaction.transaction_selector(&mut self.client, accounts)?;
                     }
                     Ok(())
                 }
 
+                /// Executes the flows on the fuzzer data and clears the accounts afterwards. The replay
+                /// log is recorded when a crash file is debugged with any of the fuzzers.
+                fn execute_iteration(&mut self, fuzzer_data: &[u8]) {
+                    let mut buf = Unstructured::new(fuzzer_data);
+
+                    if cfg!(fuzzing_debug) {
+                        ReplayLog::start();
+                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            self.execute_flows(&mut buf)
+                        }));
+                        ReplayLog::finish(&result);
+
+                        self.client.clear_accounts();
+                        if let Err(panic) = result {
+                            std::panic::resume_unwind(panic);
+                        }
+                    } else {
+                        let _ = self.execute_flows(&mut buf);
+                        self.client.clear_accounts();
+                        FuzzingStatistics::output_iteration();
+                    }
+                }
+
                 fn fuzz(&mut self) {
                     if cfg!(honggfuzz) {
                         loop {
-                            fuzz_honggfuzz(|fuzzer_data| {
-                                let mut buf = Unstructured::new(fuzzer_data);
-                                let _ = self.execute_flows(&mut buf);
-                                self.client.clear_accounts();
-                            });
+                            fuzz_honggfuzz(|fuzzer_data| self.execute_iteration(fuzzer_data));
                         }
                     } else if cfg!(afl) {
-                        fuzz_afl(true, |fuzzer_data| {
-                            let mut buf = Unstructured::new(fuzzer_data);
-                            let _ = self.execute_flows(&mut buf);
-                            self.client.clear_accounts();
-                        });
+                        if cfg!(fuzzing_debug) {
+                            ReplayLog::finish_on_panic();
+                        }
+                        fuzz_afl(true, |fuzzer_data| self.execute_iteration(fuzzer_data));
+                    } else if cfg!(libfuzzer) {
+                        if cfg!(fuzzing_debug) {
+                            ReplayLog::finish_on_panic();
+                        }
+                        fuzz_libfuzzer(|fuzzer_data| self.execute_iteration(fuzzer_data));
                     } else if cfg!(honggfuzz_debug) {
+                        CrashReport::install_panic_hook();
+
                         let mut crash_file = String::new();
                         std::io::stdin()
                             .read_line(&mut crash_file)
@@ -95,11 +261,17 @@ impl ToTokens for TridentFlowExecutorImpl {
                         let crash_file = crash_file.trim();
 
                         let fuzzer_data = std::fs::read(crash_file).expect("Failed to read crash file");
-                        let mut buf = Unstructured::new(&fuzzer_data);
-                        let _ = self.execute_flows(&mut buf);
-                        self.client.clear_accounts();
+                        self.execute_iteration(&fuzzer_data);
+                    } else if cfg!(fuzzing_coverage) {
+                        fuzz_coverage(|fuzzer_data| {
+                            let mut buf = Unstructured::new(fuzzer_data);
+                            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                self.execute_flows(&mut buf)
+                            }));
+                            self.client.clear_accounts();
+                        });
                     } else {
-                        panic!("Select Honggfuzz or AFL for fuzzing!!!")
+                        panic!("Select Honggfuzz, AFL or libFuzzer for fuzzing!!!")
                     }
                 }
             }
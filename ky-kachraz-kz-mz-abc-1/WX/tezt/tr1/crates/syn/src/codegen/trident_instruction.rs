@@ -15,17 +15,29 @@ impl ToTokens for TridentInstructionStruct {
             remaining_accounts_extension,
             remaining_accounts_snapshots,
             remaining_accounts_snapshots_after,
+            remaining_accounts_snapshots_getter,
         ) = if let Some(ref remaining_field) = self.remaining_accounts_field {
             let remaining = syn::Ident::new(remaining_field, proc_macro2::Span::call_site());
             (
                 quote! { metas.extend(self.#remaining.to_account_meta()); },
                 quote! { self.#remaining.capture_before(client); },
                 quote! { self.#remaining.capture_after(client); },
+                quote! { snapshots.extend(self.#remaining.account_snapshots()); },
             )
         } else {
-            (quote! {}, quote! {}, quote! {})
+            (quote! {}, quote! {}, quote! {}, quote! {})
         };
 
+        let remaining_accounts_rust_code =
+            if let Some(ref remaining_field) = self.remaining_accounts_field {
+                let remaining = syn::Ident::new(remaining_field, proc_macro2::Span::call_site());
+                quote! {
+                    format!("{}: {}, ", stringify!(#remaining), self.#remaining.to_rust_code())
+                }
+            } else {
+                quote! { String::new() }
+            };
+
         let debug_remaining_accounts =
             if let Some(ref remaining_field) = self.remaining_accounts_field {
                 let remaining = syn::Ident::new(remaining_field, proc_macro2::Span::call_site());
@@ -56,6 +68,30 @@ impl ToTokens for TridentInstructionStruct {
                     #remaining_accounts_extension
                     metas
                 }
+
+                /// Rust code constructing the instruction with the same data and account ids
+                fn to_rust_code(&self) -> String {
+                    format!(
+                        "{} {{ {}: {}, {}data: BorshDeserialize::try_from_slice(&{:?}).unwrap() }}",
+                        stringify!(#name),
+                        stringify!(#accounts),
+                        self.#accounts.to_rust_code(),
+                        #remaining_accounts_rust_code,
+                        borsh::to_vec(&self.data).unwrap()
+                    )
+                }
+
+                /// Authorization attacks applicable to the accounts, the remaining accounts are not attacked
+                fn get_attack_targets(&self) -> Vec<AttackTarget> {
+                    self.#accounts.account_attacks()
+                }
+
+                /// Snapshots of all accounts before and after the successful execution
+                fn get_account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+                    let mut snapshots = self.#accounts.account_snapshots();
+                    #remaining_accounts_snapshots_getter
+                    snapshots
+                }
             }
 
             // Implement InstructionSetters trait
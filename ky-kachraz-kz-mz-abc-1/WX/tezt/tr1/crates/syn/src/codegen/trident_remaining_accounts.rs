@@ -34,6 +34,27 @@ impl ToTokens for TridentRemainingAccountsStruct {
                     }
                 }
 
+                fn to_rust_code(&self) -> String {
+                    let accounts: Vec<String> = self
+                        .#field_name
+                        .iter()
+                        .map(|account| account.to_rust_code())
+                        .collect();
+                    format!(
+                        "{} {{ {}: [{}] }}",
+                        stringify!(#name),
+                        stringify!(#field_name),
+                        accounts.join(", ")
+                    )
+                }
+
+                fn account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+                    self.#field_name
+                        .iter()
+                        .filter_map(|account| account.get_snapshots())
+                        .collect()
+                }
+
                 fn to_account_meta(&mut self) -> Vec<AccountMeta> {
                     let mut metas = Vec::new();
                     for account in self.#field_name.iter() {
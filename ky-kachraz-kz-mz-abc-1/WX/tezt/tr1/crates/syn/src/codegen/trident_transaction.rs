@@ -27,6 +27,18 @@ impl ToTokens for TridentTransactionStruct {
             }
         });
 
+        // Generate instruction blocks for each field which keep the instruction data untouched
+        let instruction_accounts_blocks = self.fields.iter().map(|f| {
+            let field_ident = &f.ident;
+            quote! {
+                {
+                    self.#field_ident.resolve_accounts(client, fuzz_accounts);
+                    self.#field_ident.set_accounts(client, fuzz_accounts);
+                    self.#field_ident.set_remaining_accounts(client, fuzz_accounts);
+                }
+            }
+        });
+
         let expanded = quote! {
             // Implement the getters trait
             impl TransactionGetters for #name {
@@ -34,6 +46,10 @@ impl ToTokens for TridentTransactionStruct {
                     #name_impl
                 }
 
+                fn transaction_name() -> String {
+                    #name_impl
+                }
+
                 fn get_instruction_discriminators(&self) -> Vec<Vec<u8>> {
                     vec![
                         #(self.#field_idents.get_discriminator()),*
@@ -63,6 +79,25 @@ impl ToTokens for TridentTransactionStruct {
                         #(self.#field_idents.to_account_metas()),*
                     ]
                 }
+
+                fn to_rust_code(&self) -> String {
+                    let fields: Vec<String> = vec![
+                        #(format!("{}: {}", stringify!(#field_idents), self.#field_idents.to_rust_code())),*
+                    ];
+                    format!("{} {{ {} }}", stringify!(#name), fields.join(", "))
+                }
+
+                fn get_instruction_attack_targets(&self) -> Vec<Vec<AttackTarget>> {
+                    vec![
+                        #(self.#field_idents.get_attack_targets()),*
+                    ]
+                }
+
+                fn get_account_snapshots(&self) -> Vec<(&SnapshotAccount, &SnapshotAccount)> {
+                    let mut snapshots = Vec::new();
+                    #(snapshots.extend(self.#field_idents.get_account_snapshots());)*
+                    snapshots
+                }
             }
 
             // Implement the setters trait
@@ -88,6 +123,14 @@ impl ToTokens for TridentTransactionStruct {
                 ) {
                     #(#instruction_blocks)*
                 }
+
+                fn set_instruction_accounts(
+                    &mut self,
+                    client: &mut impl FuzzClient,
+                    fuzz_accounts: &mut Self::IxAccounts,
+                ) {
+                    #(#instruction_accounts_blocks)*
+                }
             }
 
             impl TransactionMethods for #name {
@@ -96,6 +139,9 @@ impl ToTokens for TridentTransactionStruct {
                     client: &mut impl FuzzClient,
                     fuzz_accounts: &mut Self::IxAccounts,
                 ) -> arbitrary::Result<Self> {
+                    // the invariants of the flow executor are checked after the previous
+                    // transaction once the accounts are no longer borrowed by it
+                    check_flow_invariants(client, fuzz_accounts);
                     let mut tx = Self::arbitrary(fuzzer_data)?;
                     tx.set_instructions(client, fuzz_accounts);
                     Ok(tx)
@@ -13,7 +13,9 @@ impl ToTokens for TridentSelectorEnum {
             quote! {
                 #name::#variant_name(ref mut tx) => {
                     tx.set_instructions(client, fuzz_accounts);
-                    tx.execute(client)
+                    let result = tx.execute(client);
+                    check_flow_invariants(client, fuzz_accounts);
+                    result
                 }
             }
         });
@@ -28,6 +30,11 @@ impl ToTokens for TridentSelectorEnum {
             }
         });
 
+        let variant_names = variants.iter().map(|variant| &variant.ident);
+        let variant_types = variants.iter().map(|variant| &variant.ty);
+        let variant_weights = variants.iter().map(|variant| variant.weight);
+        let variant_indices = 0..variants.len();
+
         let expanded = quote! {
             impl TransactionSelector<FuzzAccounts> for #name {
                 fn transaction_selector(
@@ -40,12 +47,24 @@ impl ToTokens for TridentSelectorEnum {
                     }
                 }
 
+                fn arbitrary_weighted(fuzzer_data: &mut FuzzerData) -> arbitrary::Result<Self> {
+                    thread_local! {
+                        static WEIGHTS: Vec<u32> = transaction_weights(&[
+                            #((<#variant_types>::transaction_name(), #variant_weights)),*
+                        ]);
+                    }
+                    match WEIGHTS.with(|weights| select_weighted(fuzzer_data, weights))? {
+                        #(#variant_indices => Ok(#name::#variant_names(Arbitrary::arbitrary(fuzzer_data)?)),)*
+                        _ => unreachable!(),
+                    }
+                }
+
                 fn select_n_execute(
                     fuzzer_data: &mut FuzzerData,
                     client: &mut impl FuzzClient,
                     accounts: &mut FuzzAccounts,
                 ) -> Result<(), FuzzingError> {
-                    let mut transaction = Self::arbitrary(fuzzer_data)?;
+                    let mut transaction = Self::arbitrary_weighted(fuzzer_data)?;
                     transaction.transaction_selector(client, accounts)
                 }
 
@@ -54,7 +73,7 @@ impl ToTokens for TridentSelectorEnum {
                     client: &mut impl FuzzClient,
                     accounts: &mut FuzzAccounts,
                 ) -> Result<(), FuzzingError> {
-                    let mut transaction = Self::arbitrary(fuzzer_data)?;
+                    let mut transaction = Self::arbitrary_weighted(fuzzer_data)?;
                     match transaction {
                         #(#process_transaction_no_hooks_match_arms)*
                     }
@@ -169,6 +169,14 @@ fn parse_constraints(attrs: &[Attribute]) -> ParseResult<TridentConstraints> {
                     constraints.skip_snapshot = true;
                     Ok(())
                 }
+                "skip_attacks" => {
+                    constraints.skip_attacks = true;
+                    Ok(())
+                }
+                "malicious" => {
+                    constraints.malicious = true;
+                    Ok(())
+                }
                 "seeds" => {
                     if meta.input.peek(syn::Token![=]) {
                         meta.input.parse::<syn::Token![=]>()?;
@@ -1,4 +1,5 @@
 use proc_macro2::TokenStream;
+use quote::ToTokens;
 use syn::parse::Error as ParseError;
 use syn::parse::Result as ParseResult;
 use syn::parse::{Parse, ParseStream};
@@ -7,6 +8,7 @@ use syn::ItemImpl;
 use syn::Meta;
 
 use crate::types::trident_flow_executor::FlowExecutorArgs;
+use crate::types::trident_flow_executor::FlowMethod;
 use crate::types::trident_flow_executor::TridentFlowExecutorImpl;
 
 impl Parse for FlowExecutorArgs {
@@ -29,6 +31,9 @@ impl Parse for FlowExecutorArgs {
                                 ));
                             }
                         }
+                    } else if nv.path.is_ident("flow_calls") {
+                        let flow_calls = parse_positive_int(&nv.value, "flow_calls")?;
+                        args.flow_calls = Some(flow_calls);
                     } else {
                         return Err(ParseError::new(
                             nv.path.span(),
@@ -37,19 +42,15 @@ impl Parse for FlowExecutorArgs {
                     }
                 }
                 Meta::Path(path) => {
-                    return Err(ParseError::new(
-                        path.span(),
-                        format!("unknown flag attribute: {}", path.get_ident().unwrap()).as_str(),
-                    ));
-                    // if path.is_ident("shuffle") {
-                    //     args.shuffle = true;
-                    // } else {
-                    //     return Err(ParseError::new(
-                    //         path.span(),
-                    //         format!("unknown flag attribute: {}", path.get_ident().unwrap())
-                    //             .as_str(),
-                    //     ));
-                    // }
+                    if path.is_ident("shuffle") {
+                        args.shuffle = true;
+                    } else {
+                        return Err(ParseError::new(
+                            path.span(),
+                            format!("unknown flag attribute: {}", path.get_ident().unwrap())
+                                .as_str(),
+                        ));
+                    }
                 }
                 _ => {
                     return Err(ParseError::new(
@@ -65,10 +66,56 @@ impl Parse for FlowExecutorArgs {
             }
         }
 
+        if args.shuffle && args.flow_calls.is_some() {
+            return Err(ParseError::new(
+                input.span(),
+                "shuffle cannot be combined with flow_calls, the flows are already selected randomly",
+            ));
+        }
+
         Ok(args)
     }
 }
 
+fn parse_positive_int(value: &syn::Expr, name: &str) -> ParseResult<u32> {
+    if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Int(lit_int),
+        ..
+    }) = value
+    {
+        let value: u32 = lit_int.base10_parse()?;
+        if value > 0 {
+            return Ok(value);
+        }
+    }
+    Err(ParseError::new(
+        value.span(),
+        format!("{} must be a positive integer", name).as_str(),
+    ))
+}
+
+/// Parses the optional `weight` of the flow, e.g. `#[flow(weight = 3)]`
+fn parse_flow_weight(attr: &syn::Attribute) -> ParseResult<u32> {
+    match &attr.meta {
+        Meta::Path(_) => Ok(1),
+        Meta::List(_) => {
+            let nv: syn::MetaNameValue = attr.parse_args()?;
+            if nv.path.is_ident("weight") {
+                parse_positive_int(&nv.value, "weight")
+            } else {
+                Err(ParseError::new(
+                    nv.path.span(),
+                    format!("unknown flow attribute: {}", nv.path.to_token_stream()).as_str(),
+                ))
+            }
+        }
+        Meta::NameValue(nv) => Err(ParseError::new(
+            nv.span(),
+            "expected #[flow] or #[flow(weight = N)]",
+        )),
+    }
+}
+
 pub fn parse_trident_flow_executor(
     attr: TokenStream,
     input: &ItemImpl,
@@ -90,8 +137,10 @@ pub fn parse_trident_flow_executor(
 
     let mut init_method = None;
     let mut flow_methods = Vec::new();
+    let mut invariant_methods = Vec::new();
+    let mut total_weight = 0u32;
 
-    // Collect init and flow methods
+    // Collect init, flow and invariant methods
     for item in &input.items {
         if let syn::ImplItem::Fn(method) = item {
             // First check for init methods
@@ -106,15 +155,53 @@ pub fn parse_trident_flow_executor(
                 continue;
             }
 
+            // Invariant methods are checked after every transaction, without access to the executor
+            if method
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("invariant"))
+            {
+                if method.sig.receiver().is_some() {
+                    return Err(ParseError::new(
+                        method.sig.span(),
+                        "#[invariant] methods cannot take self, expected fn(client: &mut C, accounts: &FuzzAccounts).",
+                    ));
+                }
+                invariant_methods.push(method.sig.ident.clone());
+                continue;
+            }
+
             // Then check for flow methods
-            if method.attrs.iter().any(|attr| attr.path().is_ident("flow")) {
+            if let Some(flow_attr) = method
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("flow"))
+            {
+                let weight = parse_flow_weight(flow_attr)?;
+                if weight != 1 && args.flow_calls.is_none() {
+                    return Err(ParseError::new(
+                        flow_attr.span(),
+                        "flow weight has an effect only with the flow_calls option of #[flow_executor]",
+                    ));
+                }
+
                 // Only check for ignore if it's a flow method
                 let is_ignored = method
                     .attrs
                     .iter()
                     .any(|attr| attr.path().is_ident("flow_ignore"));
                 if !is_ignored {
-                    flow_methods.push(method.sig.ident.clone());
+                    // The flows are selected by a number drawn from the sum of their weights
+                    total_weight = total_weight.checked_add(weight).ok_or_else(|| {
+                        ParseError::new(
+                            flow_attr.span(),
+                            "the sum of the flow weights must not exceed u32::MAX",
+                        )
+                    })?;
+                    flow_methods.push(FlowMethod {
+                        ident: method.sig.ident.clone(),
+                        weight,
+                    });
                 }
             }
         }
@@ -125,7 +212,149 @@ pub fn parse_trident_flow_executor(
         impl_block: input.items.clone(),
         flow_methods,
         init_method,
+        invariant_methods,
         generics,
         args,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quote::quote;
+    use syn::parse_quote;
+
+    fn error_message<T>(result: ParseResult<T>) -> String {
+        match result {
+            Ok(_) => panic!("expected a parse error"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_positive_int() {
+        assert_eq!(parse_positive_int(&parse_quote!(3), "weight").unwrap(), 3);
+        assert_eq!(
+            parse_positive_int(&parse_quote!(4294967295), "weight").unwrap(),
+            u32::MAX
+        );
+
+        for value in [
+            parse_quote!(0),
+            parse_quote!(-1),
+            parse_quote!(true),
+            parse_quote!("3"),
+        ] {
+            assert_eq!(
+                error_message(parse_positive_int(&value, "weight")),
+                "weight must be a positive integer"
+            );
+        }
+        assert!(parse_positive_int(&parse_quote!(4294967296), "weight").is_err());
+    }
+
+    #[test]
+    fn test_parse_flow_weight() {
+        assert_eq!(parse_flow_weight(&parse_quote!(#[flow])).unwrap(), 1);
+        assert_eq!(
+            parse_flow_weight(&parse_quote!(#[flow(weight = 3)])).unwrap(),
+            3
+        );
+
+        assert_eq!(
+            error_message(parse_flow_weight(&parse_quote!(#[flow(weight = 0)]))),
+            "weight must be a positive integer"
+        );
+        assert_eq!(
+            error_message(parse_flow_weight(&parse_quote!(#[flow(priority = 3)]))),
+            "unknown flow attribute: priority"
+        );
+        assert_eq!(
+            error_message(parse_flow_weight(&parse_quote!(#[flow = 3]))),
+            "expected #[flow] or #[flow(weight = N)]"
+        );
+    }
+
+    #[test]
+    fn test_shuffle_with_flow_calls_rejected() {
+        let args: FlowExecutorArgs = syn::parse2(quote!(shuffle)).unwrap();
+        assert!(args.shuffle);
+        let args: FlowExecutorArgs = syn::parse2(quote!(flow_calls = 5)).unwrap();
+        assert_eq!(args.flow_calls, Some(5));
+
+        for attr in [
+            quote!(shuffle, flow_calls = 5),
+            quote!(flow_calls = 5, shuffle),
+        ] {
+            assert_eq!(
+                error_message(syn::parse2::<FlowExecutorArgs>(attr)),
+                "shuffle cannot be combined with flow_calls, the flows are already selected randomly"
+            );
+        }
+    }
+
+    #[test]
+    fn test_flow_weight_requires_flow_calls() {
+        let input: ItemImpl = parse_quote! {
+            impl FuzzTest {
+                #[flow(weight = 2)]
+                fn flow1(&mut self) {}
+            }
+        };
+
+        assert_eq!(
+            error_message(parse_trident_flow_executor(quote!(), &input)),
+            "flow weight has an effect only with the flow_calls option of #[flow_executor]"
+        );
+    }
+
+    #[test]
+    fn test_flow_weights_overflow() {
+        let input: ItemImpl = parse_quote! {
+            impl FuzzTest {
+                #[flow(weight = 4294967295)]
+                fn flow1(&mut self) {}
+                #[flow]
+                #[flow_ignore]
+                fn flow2(&mut self) {}
+                #[flow]
+                fn flow3(&mut self) {}
+            }
+        };
+
+        assert_eq!(
+            error_message(parse_trident_flow_executor(quote!(flow_calls = 5), &input)),
+            "the sum of the flow weights must not exceed u32::MAX"
+        );
+    }
+
+    #[test]
+    fn test_flow_weights() {
+        let input: ItemImpl = parse_quote! {
+            impl FuzzTest {
+                #[flow(weight = 4294967294)]
+                fn flow1(&mut self) {}
+                #[flow(weight = 5)]
+                #[flow_ignore]
+                fn flow2(&mut self) {}
+                #[flow]
+                fn flow3(&mut self) {}
+            }
+        };
+
+        let flow_executor = parse_trident_flow_executor(quote!(flow_calls = 5), &input).unwrap();
+        let weights = flow_executor
+            .flow_methods
+            .iter()
+            .map(|method| (method.ident.to_string(), method.weight))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            weights,
+            vec![
+                ("flow1".to_string(), u32::MAX - 1),
+                ("flow3".to_string(), 1)
+            ]
+        );
+    }
+}
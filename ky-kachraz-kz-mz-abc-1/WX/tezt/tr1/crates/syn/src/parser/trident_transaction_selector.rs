@@ -1,7 +1,7 @@
 use syn::parse::Error as ParseError;
 use syn::parse::Result as ParseResult;
 use syn::spanned::Spanned;
-use syn::{Data, DeriveInput};
+use syn::{Data, DeriveInput, Fields, Variant};
 
 use crate::types::trident_transaction_selector::TridentSelectorEnum;
 use crate::types::trident_transaction_selector::TridentSelectorVariant;
@@ -13,11 +13,7 @@ pub fn parse_trident_selector(input: &DeriveInput) -> ParseResult<TridentSelecto
         Data::Enum(enum_data) => enum_data
             .variants
             .iter()
-            .map(|variant| {
-                Ok(TridentSelectorVariant {
-                    ident: variant.ident.clone(),
-                })
-            })
+            .map(parse_selector_variant)
             .collect::<ParseResult<Vec<_>>>(),
         _ => Err(ParseError::new(
             input.span(),
@@ -27,3 +23,31 @@ pub fn parse_trident_selector(input: &DeriveInput) -> ParseResult<TridentSelecto
 
     Ok(TridentSelectorEnum { ident, variants })
 }
+
+fn parse_selector_variant(variant: &Variant) -> ParseResult<TridentSelectorVariant> {
+    let ty = match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => fields.unnamed[0].ty.clone(),
+        _ => {
+            return Err(ParseError::new(
+                variant.span(),
+                "Selector variants must contain exactly one transaction",
+            ))
+        }
+    };
+
+    // Variants are selected with the default weight of 1 unless specified by #[weight(N)]
+    let weight = match variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("weight"))
+    {
+        Some(attr) => attr.parse_args::<syn::LitInt>()?.base10_parse::<u32>()?,
+        None => 1,
+    };
+
+    Ok(TridentSelectorVariant {
+        ident: variant.ident.clone(),
+        ty,
+        weight,
+    })
+}
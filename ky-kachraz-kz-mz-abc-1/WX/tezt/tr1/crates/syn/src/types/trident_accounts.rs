@@ -50,6 +50,8 @@ pub struct TridentConstraints {
     pub signer: bool,
     pub address: Option<syn::Expr>,
     pub skip_snapshot: bool,
+    pub skip_attacks: bool,
+    pub malicious: bool,
     pub storage: Option<Ident>,
     pub seeds: Option<Vec<syn::Expr>>, // Store the raw expressions from the array
     pub program_id: Option<syn::Expr>,
@@ -3,14 +3,22 @@ use syn::{Generics, Ident, ImplItem};
 pub struct TridentFlowExecutorImpl {
     pub type_name: Box<syn::Type>,
     pub impl_block: Vec<ImplItem>,
-    pub flow_methods: Vec<Ident>,
+    pub flow_methods: Vec<FlowMethod>,
     pub init_method: Option<Ident>,
+    pub invariant_methods: Vec<Ident>,
     pub generics: Generics,
     pub args: FlowExecutorArgs,
 }
 
+pub struct FlowMethod {
+    pub ident: Ident,
+    pub weight: u32,
+}
+
 #[derive(Debug, Default)]
 pub struct FlowExecutorArgs {
     pub random_tail: bool,
+    pub shuffle: bool,
+    pub flow_calls: Option<u32>,
     // More fields can be added here in the future
 }
@@ -7,4 +7,6 @@ pub struct TridentSelectorEnum {
 
 pub struct TridentSelectorVariant {
     pub ident: Ident,
+    pub ty: syn::Type,
+    pub weight: u32,
 }
@@ -24,6 +24,36 @@ impl Template {
             }
             IdlTypeDefTy::Type { alias: _ } => self.process_type(),
         });
+
+        // program accounts can be deserialized from the account snapshots
+        // and program events can be decoded from the transaction logs
+        let accounts = idl
+            .accounts
+            .iter()
+            .map(|account| (&account.name, &account.discriminator));
+        let events = idl
+            .events
+            .iter()
+            .map(|event| (&event.name, &event.discriminator));
+        accounts
+            .chain(events)
+            .filter(|(name, _)| idl.types.iter().any(|type_def| &type_def.name == *name))
+            .for_each(|(name, discriminator)| self.process_discriminator(name, discriminator));
+    }
+
+    fn process_discriminator(&mut self, name: &str, discriminator: &[u8]) {
+        let type_ident = format_ident!("{}", name);
+        let discriminator: Vec<syn::LitInt> = discriminator
+            .iter()
+            .map(|byte| syn::parse_str(&byte.to_string()).unwrap())
+            .collect();
+
+        let discriminator_impl: syn::Item = parse_quote! {
+            impl Discriminator for #type_ident {
+                const DISCRIMINATOR: &'static [u8] = &[#(#discriminator),*];
+            }
+        };
+        self.custom_types.push(discriminator_impl);
     }
     fn process_struct(&mut self, type_def: &IdlTypeDef, struct_fields: &Option<IdlDefinedFields>) {
         let type_name = &type_def.name;
@@ -106,13 +136,15 @@ impl Template {
         let type_name = &type_def.name;
         let type_ident = format_ident!("{}", type_name);
 
-        let fields = idl_fields
+        // struct fields are public, so they can be compared within invariant checks
+        let fields: Vec<syn::Field> = idl_fields
             .iter()
-            .fold(Vec::new(), |mut named_fields, field| {
-                // process each field in struct
-                self.process_struct_field(field, &mut named_fields);
-                named_fields
-            });
+            .map(|field| {
+                let field_ident = format_ident!("{}", field.name);
+                let (field_type, _is_custom) = idl_type_to_syn_type(&field.ty);
+                parse_quote!(pub #field_ident: #field_type)
+            })
+            .collect();
 
         // if the struct corresponds to a program account we do not add arbitrary
         let struct_definition: syn::Item = parse_quote! {
@@ -65,11 +65,13 @@ impl Template {
             }
 
 
-            fn main() {
-
-                let client = TridentSVM::new_client(&[], &TridentConfig::new());
+            /// Creates the client for the fuzz test and the regression tests
+            fn new_client() -> TridentSVM {
+                TridentSVM::new_client(&[], &TridentConfig::new())
+            }
 
-                FuzzTest::new(client).fuzz();
+            fn main() {
+                FuzzTest::new(new_client()).fuzz();
             }
         };
 